@@ -49,7 +49,7 @@ unsafe extern "system" fn WindowProc(hwnd: HWND, uMsg: UINT, wParam: WPARAM, lPa
     DefWindowProcA(hwnd, uMsg, wParam, lParam)
 }
 
-unsafe extern "stdcall" fn DebugReportCallback(flags: VkDebugReportFlagsEXT,
+unsafe extern "system" fn DebugReportCallback(flags: VkDebugReportFlagsEXT,
                                                objectType: VkDebugReportObjectTypeEXT,
                                                object: uint64_t,
                                                location: size_t,
@@ -0,0 +1,9 @@
+#!/bin/sh
+# Downloads the vk.xml that dvk-generator reads by default, pinned to REGISTRY_VERSION in generator/src/lib.rs,
+# and checks it against vk.xml.sha256. Update the checksum file together with the version.
+set -e
+cd "$(dirname "$0")"
+version=$(sed -n 's/^pub const REGISTRY_VERSION: &str = "\(.*\)";$/\1/p' ../src/lib.rs)
+curl -fsSL -o vk.xml.download "https://raw.githubusercontent.com/KhronosGroup/Vulkan-Docs/$version/xml/vk.xml"
+mv vk.xml.download vk.xml
+sha256sum -c vk.xml.sha256
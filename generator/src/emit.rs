@@ -0,0 +1,1148 @@
+//! Emits the registry as modules formatted like the hand-written `src/lib.rs`
+//!
+//! Every type, constant and command goes to the module of the first core version or extension
+//! that needs it. Enum values added by extensions are emitted with the enum they extend.
+//! The version macros, the `*Union` helper enums and the `device` module stay hand-written.
+
+use ::std::collections::{HashMap, HashSet};
+use ::std::fmt::Write;
+use ::registry::{Registry, Interface, TypeKind, CType, Member, Value, EnumValue};
+use ::GeneratorError;
+
+const IND: &str = "    ";
+
+/// The extensions that `src/lib.rs` has modules for
+pub const HAND_WRITTEN_EXTENSIONS: &[&str] = &["VK_KHR_surface",
+                                               "VK_KHR_swapchain",
+                                               "VK_KHR_display",
+                                               "VK_KHR_display_swapchain",
+                                               "VK_KHR_xlib_surface",
+                                               "VK_KHR_xcb_surface",
+                                               "VK_KHR_wayland_surface",
+                                               "VK_KHR_win32_surface",
+                                               "VK_KHR_get_physical_device_properties2",
+                                               "VK_KHR_external_memory_capabilities",
+                                               "VK_KHR_external_memory",
+                                               "VK_KHR_external_memory_fd",
+                                               "VK_KHR_external_semaphore_capabilities",
+                                               "VK_KHR_external_semaphore",
+                                               "VK_KHR_external_semaphore_fd",
+                                               "VK_EXT_direct_mode_display",
+                                               "VK_KHR_external_fence_capabilities",
+                                               "VK_KHR_external_fence",
+                                               "VK_KHR_external_fence_fd",
+                                               "VK_EXT_external_memory_dma_buf",
+                                               "VK_KHR_dynamic_rendering",
+                                               "VK_EXT_debug_report",
+                                               "VK_EXT_debug_utils",
+                                               "VK_EXT_image_drm_format_modifier",
+                                               "VK_KHR_timeline_semaphore",
+                                               "VK_EXT_headless_surface",
+                                               "VK_EXT_acquire_drm_display",
+                                               "VK_KHR_synchronization2"];
+
+/// Commands that are loaded without an instance
+const GLOBAL_COMMANDS: &[&str] = &["vkCreateInstance",
+                                   "vkEnumerateInstanceExtensionProperties",
+                                   "vkEnumerateInstanceLayerProperties",
+                                   "vkEnumerateInstanceVersion"];
+
+const LIBC_TYPES: &[&str] = &["c_void", "c_char", "c_int", "c_float", "c_double", "size_t"];
+
+/// Fixed width integer types, imported from the crate's `ctypes` module
+const FIXED_WIDTH_TYPES: &[&str] = &["uint8_t", "uint16_t", "uint32_t", "uint64_t", "int8_t", "int16_t", "int32_t", "int64_t"];
+
+const CORE_MACROS: &str = r#"    #[macro_export]
+    macro_rules! VK_MAKE_VERSION {
+        ($major:expr, $minor:expr, $patch:expr) => ((($major) << 22) | (($minor) << 12) | ($patch));
+    }
+
+    #[macro_export]
+    macro_rules! VK_DEFINE_NON_DISPATCHABLE_HANDLE {
+        ($name:ident) => (
+            #[derive(Clone)]
+            #[derive(Copy)]
+            #[derive(Debug)]
+            #[repr(C)]
+            pub struct $name(uint64_t);
+            impl $name {
+                pub fn null() -> $name {
+                    $name(0)
+                }
+                pub fn is_null(&self) -> bool {
+                    self.0 == 0
+                }
+            }
+        );
+    }
+
+    #[macro_export]
+    macro_rules! VK_DEFINE_HANDLE {
+        ($name:ident) => (
+            #[derive(Clone)]
+            #[derive(Copy)]
+            #[derive(Debug)]
+            #[repr(C)]
+            pub struct $name(*const c_void);
+            impl $name {
+                pub fn null() -> $name {
+                    $name(::std::ptr::null())
+                }
+                pub fn is_null(&self) -> bool {
+                    self.0.is_null()
+                }
+            }
+        );
+    }
+"#;
+
+/// A generated `pub mod`
+pub struct Module {
+    pub name: String,
+    pub interface: String,
+    pub text: String
+}
+
+/// Everything a module defines, in the order it was required
+#[derive(Default)]
+struct Contents {
+    constants: Vec<String>,
+    extension_constants: Vec<(String, Value)>,
+    types: Vec<String>,
+    commands: Vec<String>,
+    /// Aliases of enum values as (alias, enum, target)
+    value_aliases: Vec<(String, String, String)>
+}
+
+/// `core` for Vulkan 1.0, `core_1_1` for Vulkan 1.1 and `khr_surface` for VK_KHR_surface
+pub fn module_name(interface: &str) -> String {
+    if interface == "VK_VERSION_1_0" {
+        String::from("core")
+    } else if let Some(version) = interface.strip_prefix("VK_VERSION_") {
+        format!("core_{}", version)
+    } else {
+        interface.trim_start_matches("VK_").to_lowercase()
+    }
+}
+
+/// `VkCoreCommands`, `VkCore11Commands` and `VkKhrSurfaceCommands`
+pub fn table_name(interface: &str) -> String {
+    if let Some(version) = interface.strip_prefix("VK_VERSION_") {
+        return format!("VkCore{}Commands", if version == "1_0" { String::new() } else { version.replace('_', "") });
+    }
+    let mut name = String::from("Vk");
+    for part in interface.trim_start_matches("VK_").split('_') {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            name.extend(first.to_uppercase());
+            name.push_str(&chars.as_str().to_lowercase());
+        }
+    }
+    name + "Commands"
+}
+
+/// Maps `VkSampleCountFlagBits` to `VkSampleCountFlags`, dvk merges the bits into the flags type
+fn flags_name(name: &str) -> String {
+    name.replacen("FlagBits", "Flags", 1)
+}
+
+fn fn_name(pfn: &str) -> String {
+    format!("{}Fn", pfn.trim_start_matches("PFN_"))
+}
+
+fn base_type(name: &str) -> String {
+    match name {
+        "void" => String::from("c_void"),
+        "char" => String::from("c_char"),
+        "float" => String::from("c_float"),
+        "double" => String::from("c_double"),
+        "int" => String::from("c_int"),
+        _ if name.starts_with("PFN_") => fn_name(name),
+        _ => flags_name(name)
+    }
+}
+
+/// The Rust spelling of a registry type, e.g. `*const *const c_char` or `[c_float;4]`
+pub fn rust_type(ty: &CType) -> String {
+    let mut text = base_type(&ty.name);
+    // Function pointers are nullable, except vkVoidFunctionFn which is a plain pointer in dvk
+    if ty.name.starts_with("PFN_") && ty.name != "PFN_vkVoidFunction" && ty.pointers.is_empty() && ty.array.is_empty() {
+        return format!("Option<{}>", text);
+    }
+    for &is_const in ty.pointers.iter().rev() {
+        text = format!("{} {}", if is_const { "*const" } else { "*mut" }, text);
+    }
+    for dimension in ty.array.iter().rev() {
+        text = format!("[{};{}]", text, dimension);
+    }
+    text
+}
+
+/// `type` is a keyword, dvk names those members after their type like `dType` and `iType`
+pub fn field_name(member: &Member) -> String {
+    if member.name == "type" {
+        let stripped = member.ty.name.trim_start_matches("Vk");
+        let first = stripped.chars().next().unwrap_or('t').to_lowercase().collect::<String>();
+        format!("{}Type", first)
+    } else {
+        member.name.clone()
+    }
+}
+
+fn is_builtin(name: &str) -> bool {
+    LIBC_TYPES.contains(&name) || FIXED_WIDTH_TYPES.contains(&name) || ["void", "char", "float", "double", "int"].contains(&name)
+}
+
+struct Generator<'a> {
+    registry: &'a Registry,
+    interfaces: Vec<&'a Interface>,
+    owner: HashMap<String, usize>,
+    contents: Vec<Contents>,
+    extra_values: HashMap<String, Vec<EnumValue>>,
+    array_constants: HashSet<String>,
+    /// Types of the extension constants, for their aliases
+    constant_types: HashMap<String, String>
+}
+
+impl<'a> Generator<'a> {
+    fn claim_constant(&mut self, name: &str, module: usize) {
+        if self.owner.contains_key(name) || self.registry.constant(name).is_none() {
+            return;
+        }
+        self.owner.insert(name.to_string(), module);
+        self.contents[module].constants.push(name.to_string());
+    }
+
+    fn claim_ctype(&mut self, ty: &CType, module: usize) {
+        for dimension in &ty.array {
+            if super::registry::parse_int(dimension).is_none() {
+                self.claim_constant(dimension, module);
+            }
+        }
+        let name = if self.registry.type_def(&flags_name(&ty.name)).is_some() { flags_name(&ty.name) } else { ty.name.clone() };
+        self.claim_type(&name, module);
+    }
+
+    fn claim_type(&mut self, name: &str, module: usize) {
+        if is_builtin(name) || self.owner.contains_key(name) {
+            return;
+        }
+        let def = match self.registry.type_def(name) {
+            Some(def) => def,
+            None => return
+        };
+        if let TypeKind::Other = def.kind {
+            if def.alias.is_none() {
+                return;
+            }
+        }
+        self.owner.insert(name.to_string(), module);
+        self.contents[module].types.push(name.to_string());
+        if let Some(ref alias) = def.alias {
+            let alias = alias.clone();
+            self.claim_type(&flags_name(&alias), module);
+        }
+        match def.kind {
+            TypeKind::Struct { ref members, .. } | TypeKind::Union { ref members } => {
+                for member in members {
+                    self.claim_ctype(&member.ty, module);
+                }
+            },
+            TypeKind::FuncPointer { ref ret, ref params } => {
+                self.claim_ctype(ret, module);
+                for param in params {
+                    self.claim_ctype(&param.ty, module);
+                }
+            },
+            TypeKind::BaseType(Some(ref ty)) => self.claim_ctype(ty, module),
+            TypeKind::Bitmask { width64, .. } => self.claim_type(if width64 { "VkFlags64" } else { "VkFlags" }, module),
+            _ => {}
+        }
+    }
+
+    fn claim_command(&mut self, name: &str, module: usize) {
+        if self.owner.contains_key(name) {
+            return;
+        }
+        let command = match self.registry.command(name) {
+            Some(command) => command,
+            None => return
+        };
+        self.owner.insert(name.to_string(), module);
+        self.contents[module].commands.push(name.to_string());
+        self.claim_ctype(&command.ret, module);
+        for param in &command.params {
+            self.claim_ctype(&param.ty, module);
+        }
+    }
+
+    fn add_value(&mut self, extends: &str, name: &str, value: &Value) {
+        let known = self.registry.enum_group(extends).map(|group| group.values.iter().any(|known| known.name == name)).unwrap_or(false);
+        let values = self.extra_values.entry(extends.to_string()).or_default();
+        if known || values.iter().any(|known| known.name == name) {
+            return;
+        }
+        values.push(EnumValue { name: name.to_string(), value: value.clone(), ty: None });
+    }
+
+    fn collect(&mut self) {
+        for module in 0..self.interfaces.len() {
+            let interface = self.interfaces[module];
+            for require in &interface.requires {
+                for required in &require.enums {
+                    match (required.extends.as_ref(), required.value.as_ref()) {
+                        (Some(extends), Some(Value::Alias(target))) => {
+                            let alias = (required.name.clone(), flags_name(extends), target.clone());
+                            if !self.contents.iter().any(|contents| contents.value_aliases.contains(&alias)) {
+                                self.contents[module].value_aliases.push(alias);
+                            }
+                        },
+                        (Some(extends), Some(value)) => self.add_value(extends, &required.name, value),
+                        (None, Some(value)) => {
+                            if self.owner.contains_key(&required.name) {
+                                continue;
+                            }
+                            self.owner.insert(required.name.clone(), module);
+                            self.contents[module].extension_constants.push((required.name.clone(), value.clone()));
+                        },
+                        _ => self.claim_constant(&required.name, module)
+                    }
+                }
+                for name in &require.types {
+                    self.claim_type(&flags_name(name), module);
+                }
+                for name in &require.commands {
+                    self.claim_command(name, module);
+                }
+            }
+        }
+    }
+
+    fn uses_negative(&self, group: &str) -> bool {
+        let registry_values = self.registry.enum_group(group).map(|group| group.values.clone()).unwrap_or_default();
+        registry_values.iter().chain(self.extra_values.get(group).into_iter().flatten()).any(|value| match value.value {
+            Value::Int(value) => value < 0,
+            _ => false
+        })
+    }
+
+    /// Integer value of an enum value, following aliases
+    fn resolve(&self, group: &str, value: &Value) -> Option<i64> {
+        match *value {
+            Value::Int(value) => Some(value),
+            Value::Alias(ref target) => {
+                let registry_values = self.registry.enum_group(group).map(|group| group.values.as_slice()).unwrap_or(&[]);
+                registry_values.iter()
+                    .chain(self.extra_values.get(group).into_iter().flatten())
+                    .find(|candidate| &candidate.name == target)
+                    .and_then(|candidate| self.resolve(group, &candidate.value))
+            },
+            _ => None
+        }
+    }
+
+    /// Size and alignment of a type on a 64-bit target, used for the byte arrays backing unions
+    fn layout(&self, ty: &CType) -> (usize, usize) {
+        let mut count = 1;
+        for dimension in &ty.array {
+            count *= match super::registry::parse_int(dimension) {
+                Some(count) => count as usize,
+                None => match self.registry.constant(dimension).map(|constant| &constant.value) {
+                    Some(&Value::Int(count)) => count as usize,
+                    _ => 1
+                }
+            };
+        }
+        let (size, align) = if !ty.pointers.is_empty() {
+            (8, 8)
+        } else {
+            self.named_layout(&flags_name(&ty.name))
+        };
+        (size * count, align)
+    }
+
+    fn named_layout(&self, name: &str) -> (usize, usize) {
+        match name {
+            "char" | "uint8_t" | "int8_t" => return (1, 1),
+            "uint16_t" | "int16_t" => return (2, 2),
+            "float" | "int" | "uint32_t" | "int32_t" => return (4, 4),
+            "double" | "uint64_t" | "int64_t" | "size_t" => return (8, 8),
+            _ => {}
+        }
+        let def = match self.registry.type_def(name) {
+            Some(def) => def,
+            None => return (8, 8)
+        };
+        if let Some(ref alias) = def.alias {
+            return self.named_layout(&flags_name(alias));
+        }
+        match def.kind {
+            TypeKind::Enum => (4, 4),
+            TypeKind::Bitmask { width64, .. } => if width64 { (8, 8) } else { (4, 4) },
+            TypeKind::BaseType(Some(ref ty)) => self.layout(ty),
+            TypeKind::Struct { ref members, .. } => {
+                let (mut size, mut align) = (0usize, 1);
+                for member in members {
+                    let (member_size, member_align) = self.layout(&member.ty);
+                    size = size.div_ceil(member_align) * member_align + member_size;
+                    align = align.max(member_align);
+                }
+                (size.div_ceil(align) * align, align)
+            },
+            TypeKind::Union { ref members } => {
+                let (mut size, mut align) = (0usize, 1);
+                for member in members {
+                    let (member_size, member_align) = self.layout(&member.ty);
+                    size = size.max(member_size);
+                    align = align.max(member_align);
+                }
+                (size.div_ceil(align) * align, align)
+            },
+            _ => (8, 8)
+        }
+    }
+
+    fn constant_text(&self, name: &str) -> String {
+        let constant = self.registry.constant(name).unwrap();
+        if self.array_constants.contains(name) {
+            if let Value::Int(value) = constant.value {
+                return format!("{}pub const {}:size_t = {}usize;\n", IND, name, value);
+            }
+        }
+        let ty = match constant.ty.as_deref() {
+            Some("float") => "c_float",
+            Some("uint64_t") => "uint64_t",
+            Some("int32_t") => "int32_t",
+            _ => match constant.value {
+                Value::Float(_) => "c_float",
+                Value::Expr(ref expr) if expr.contains("ULL") => "uint64_t",
+                _ => "uint32_t"
+            }
+        };
+        let suffix = match ty {
+            "c_float" => "f32",
+            "uint64_t" => "u64",
+            "int32_t" => "i32",
+            _ => "u32"
+        };
+        let value = match constant.value {
+            Value::Int(value) => format!("{}{}", value, suffix),
+            Value::Float(ref value) => format!("{}{}", value, suffix),
+            Value::Expr(ref expr) => {
+                // (~0U), (~1U) and (~0ULL)
+                let inner = expr.trim_matches(['(', ')']).trim_start_matches('~').trim_end_matches(['U', 'L']);
+                match super::registry::parse_int(inner) {
+                    Some(0) => format!("!0x0{}", suffix),
+                    Some(offset) => format!("!0x0{} - {}", suffix, offset),
+                    None => expr.clone()
+                }
+            },
+            Value::Alias(ref target) => target.clone(),
+            Value::Str(ref text) => format!("{:?}", text)
+        };
+        format!("{}pub const {}:{} = {};\n", IND, name, ty, value)
+    }
+
+    fn extension_constant_text(&self, name: &str, value: &Value) -> Option<String> {
+        match *value {
+            Value::Int(value) => Some(format!("{}pub const {}: uint32_t = {};\n", IND, name, value)),
+            Value::Str(ref text) => Some(format!("{}pub const {}: *const c_char = b\"{}\\0\" as *const u8 as *const c_char;\n", IND, name, text)),
+            Value::Alias(ref target) => {
+                let ty = self.constant_types.get(target).cloned().or_else(|| {
+                    self.registry.constant(target).map(|_| {
+                        let text = self.constant_text(target);
+                        text.split(':').nth(1).and_then(|rest| rest.split(" =").next()).unwrap_or("uint32_t").trim().to_string()
+                    })
+                });
+                ty.map(|ty| format!("{}pub const {}: {} = {};\n", IND, name, ty, target))
+            },
+            _ => None
+        }
+    }
+
+    fn enum_text(&self, name: &str, extension: bool) -> String {
+        let mut variants = Vec::new();
+        let registry_values = self.registry.enum_group(name).map(|group| group.values.as_slice()).unwrap_or(&[]);
+        for value in registry_values.iter().chain(self.extra_values.get(name).into_iter().flatten()) {
+            if let Value::Int(number) = value.value {
+                variants.push(format!("{}{}{} = {}", IND, IND, value.name, number));
+            }
+        }
+        let repr = if extension || self.uses_negative(name) { "i32" } else { "u32" };
+        let mut text = String::new();
+        for derive in &[format!("#[repr({})]", repr), "#[derive(Eq)]".to_string(), "#[derive(PartialEq)]".to_string(),
+                        "#[derive(Debug)]".to_string(), "#[derive(Copy)]".to_string(), "#[derive(Clone)]".to_string()] {
+            let _ = writeln!(text, "{}{}", IND, derive);
+        }
+        let _ = writeln!(text, "{}pub enum {} {{", IND, name);
+        let _ = writeln!(text, "{}", variants.join(",\n"));
+        let _ = writeln!(text, "{}}}", IND);
+        text
+    }
+
+    fn flags_text(&self, name: &str, bits: Option<&str>, width64: bool) -> String {
+        let ty = if width64 { "VkFlags64" } else { "VkFlags" };
+        let mut values = Vec::new();
+        if let Some(bits) = bits {
+            let registry_values = self.registry.enum_group(bits).map(|group| group.values.as_slice()).unwrap_or(&[]);
+            for value in registry_values.iter().chain(self.extra_values.get(bits).into_iter().flatten()) {
+                if let Some(number) = self.resolve(bits, &value.value) {
+                    values.push(format!("{}const {} = 0x{:0width$x}", IND.repeat(3), value.name, number, width = if width64 { 16 } else { 8 }));
+                }
+            }
+        }
+        if values.is_empty() {
+            return format!("{}reserved_bitflags! {{\n{}pub flags {}: {};\n{}}}\n", IND, IND.repeat(2), name, ty, IND);
+        }
+        format!("{}bitflags! {{\n{}#[repr(transparent)]\n{}pub flags {}: {} {{\n{}\n{}}}\n{}}}\n",
+                IND, IND.repeat(2), IND.repeat(2), name, ty, values.join(",\n"), IND.repeat(2), IND)
+    }
+
+    fn struct_text(&self, name: &str, members: &[Member], union: bool) -> String {
+        let mut fields = Vec::new();
+        if union {
+            let (size, _) = self.named_layout(name);
+            fields.push(format!("{}union_data: [u8;{}]", IND.repeat(2), size));
+        } else {
+            let mut index = 0;
+            while index < members.len() {
+                let member = &members[index];
+                if member.ty.bitfield.is_some() {
+                    // Bit-fields have no Rust equivalent, consecutive ones are packed into one integer
+                    let mut names = vec![member.name.clone()];
+                    let mut bits = member.ty.bitfield.unwrap_or(0);
+                    while bits < 32 && index + 1 < members.len() && members[index + 1].ty.bitfield.is_some() {
+                        index += 1;
+                        bits += members[index].ty.bitfield.unwrap_or(0);
+                        let next = &members[index].name;
+                        names.push(next[..1].to_uppercase() + &next[1..]);
+                    }
+                    fields.push(format!("{}pub {}: uint32_t", IND.repeat(2), names.join("And")));
+                } else {
+                    fields.push(format!("{}pub {}: {}", IND.repeat(2), field_name(member), rust_type(&member.ty)));
+                }
+                index += 1;
+            }
+        }
+        format!("{}#[repr(C)]\n{}#[derive(Copy)]\n{}#[derive(Clone)]\n{}pub struct {} {{\n{}\n{}}}\n",
+                IND, IND, IND, IND, name, fields.join(",\n"), IND)
+    }
+
+    fn signature(name: &str, params: &[Member], ret: &CType) -> String {
+        let head = format!("{}pub type {} = unsafe extern \"system\" fn(", IND, name);
+        let pad = " ".repeat(head.len());
+        let params: Vec<String> = params.iter().map(|param| format!("{}: {}", field_name(param), rust_type(&param.ty))).collect();
+        let mut text = head + &params.join(&format!(",\n{}", pad)) + ")";
+        if !ret.is_void() {
+            text = text + " -> " + &rust_type(ret);
+        }
+        text + ";\n"
+    }
+
+    fn type_text(&self, name: &str, module: usize) -> Option<String> {
+        let def = self.registry.type_def(name).unwrap();
+        if let Some(ref alias) = def.alias {
+            return Some(format!("{}pub type {} = {};\n", IND, name, flags_name(alias)));
+        }
+        let extension = self.interfaces[module].is_extension;
+        match def.kind {
+            TypeKind::BaseType(Some(ref ty)) => Some(format!("{}pub type {} = {};\n", IND, name, rust_type(ty))),
+            TypeKind::Bitmask { ref bits, width64 } => Some(self.flags_text(name, bits.as_ref().map(|bits| bits.as_str()), width64)),
+            TypeKind::Enum => Some(self.enum_text(name, extension)),
+            TypeKind::Struct { ref members, .. } => Some(self.struct_text(name, members, false)),
+            TypeKind::Union { ref members } => Some(self.struct_text(name, members, true)),
+            TypeKind::FuncPointer { ref ret, ref params } => {
+                if name == "PFN_vkVoidFunction" {
+                    Some(format!("{}pub type vkVoidFunctionFn = *const u8;\n", IND))
+                } else {
+                    Some(Generator::signature(&fn_name(name), params, ret))
+                }
+            },
+            _ => None
+        }
+    }
+
+    fn module_text(&self, module: usize) -> String {
+        let interface = self.interfaces[module];
+        let contents = &self.contents[module];
+        let mut sections: Vec<String> = Vec::new();
+        if interface.name == "VK_VERSION_1_0" {
+            sections.push(CORE_MACROS.to_string());
+        }
+        if let Some(version) = interface.name.strip_prefix("VK_VERSION_") {
+            let version = version.replace('_', ",");
+            sections.push(format!("{}pub const VK_API_VERSION_{}: uint32_t = VK_MAKE_VERSION!({},0);\n", IND, interface.name.trim_start_matches("VK_VERSION_"), version));
+        }
+        let kind_of = |name: &String| self.registry.type_def(name).map(|def| {
+            if def.alias.is_some() {
+                6
+            } else {
+                match def.kind {
+                    TypeKind::BaseType(_) => 0,
+                    TypeKind::Handle { .. } => 1,
+                    TypeKind::Enum => 2,
+                    TypeKind::Bitmask { .. } => 3,
+                    TypeKind::FuncPointer { .. } => 4,
+                    _ => 5
+                }
+            }
+        }).unwrap_or(7);
+        let base_types: String = contents.types.iter().filter(|name| kind_of(name) == 0).filter_map(|name| self.type_text(name, module)).collect();
+        if !base_types.is_empty() {
+            sections.push(base_types);
+        }
+        let handles: String = contents.types.iter().filter(|name| kind_of(name) == 1).map(|name| {
+            match self.registry.type_def(name).map(|def| &def.kind) {
+                Some(&TypeKind::Handle { dispatchable: true }) => format!("{}VK_DEFINE_HANDLE!({});\n", IND, name),
+                _ => format!("{}VK_DEFINE_NON_DISPATCHABLE_HANDLE!({});\n", IND, name)
+            }
+        }).collect();
+        if !handles.is_empty() {
+            sections.push(handles);
+        }
+        let mut constants: String = contents.extension_constants.iter().filter_map(|(name, value)| self.extension_constant_text(name, value)).collect();
+        for (alias, ty, target) in &contents.value_aliases {
+            let bitmask = self.registry.type_def(ty).map(|def| matches!(def.kind, TypeKind::Bitmask { .. })).unwrap_or(false);
+            if bitmask {
+                let _ = writeln!(constants, "{}pub const {}: {} = {};", IND, alias, ty, target);
+            } else {
+                let _ = writeln!(constants, "{}pub const {}: {} = {}::{};", IND, alias, ty, ty, target);
+            }
+        }
+        for name in contents.types.iter().filter(|name| kind_of(name) == 2) {
+            if let Some(group) = self.registry.enum_group(name) {
+                for value in &group.values {
+                    if let Value::Alias(ref target) = value.value {
+                        let _ = writeln!(constants, "{}pub const {}: {} = {}::{};", IND, value.name, name, name, target);
+                    }
+                }
+            }
+        }
+        constants.extend(contents.constants.iter().map(|name| self.constant_text(name)));
+        if !constants.is_empty() {
+            sections.push(constants);
+        }
+        for kind in 2..7 {
+            for name in contents.types.iter().filter(|name| kind_of(name) == kind) {
+                if let Some(text) = self.type_text(name, module) {
+                    sections.push(text);
+                }
+            }
+        }
+        let commands: Vec<Command> = contents.commands.iter().map(|name| {
+            let command = self.registry.command(name).unwrap();
+            Command { name: name.clone(), params: command.params.clone(), ret: command.ret.clone() }
+        }).collect();
+        for command in &commands {
+            sections.push(Generator::signature(&format!("{}Fn", command.name), &command.params, &command.ret));
+        }
+        if !commands.is_empty() {
+            sections.push(if interface.name == "VK_VERSION_1_0" {
+                core_table(&commands)
+            } else if interface.is_extension {
+                extension_table(&interface.name, &commands)
+            } else {
+                versioned_table(&interface.name, &commands)
+            });
+        }
+        let body = sections.join("\n");
+        let mut header = String::new();
+        let libc: Vec<&str> = LIBC_TYPES.iter().cloned().filter(|name| contains_word(&body, name)).collect();
+        if !libc.is_empty() {
+            let _ = writeln!(header, "{}use ::libc::{{{}}};", IND, libc.join(", "));
+        }
+        let fixed_width: Vec<&str> = FIXED_WIDTH_TYPES.iter().cloned().filter(|name| contains_word(&body, name)).collect();
+        if !fixed_width.is_empty() {
+            let _ = writeln!(header, "{}use ::ctypes::{{{}}};", IND, fixed_width.join(", "));
+        }
+        if !commands.is_empty() {
+            let _ = writeln!(header, "{}use ::shared_library::dynamic_library::DynamicLibrary;", IND);
+            let _ = writeln!(header, "{}use ::std::sync::Arc;", IND);
+            let _ = writeln!(header, "{}use ::std::ffi::CString;", IND);
+            let _ = writeln!(header, "{}use ::loader::{{LoaderConfig, Entry, NotLoaded, LoadError, LoadReport}};", IND);
+            let _ = writeln!(header, "{}use ::Command;", IND);
+        }
+        let mut dependencies: Vec<usize> = self.owner.iter()
+            .filter(|&(name, &owner)| owner != module && contains_word(&body, name))
+            .map(|(_, &owner)| owner)
+            .collect();
+        dependencies.sort();
+        dependencies.dedup();
+        for dependency in dependencies {
+            let _ = writeln!(header, "{}use ::{}::*;", IND, module_name(&self.interfaces[dependency].name));
+        }
+        let attributes = if body.contains("    bitflags! {") {
+            "// bitflags 0.7 expands to the deprecated try! macro\n#[allow(deprecated)]\n"
+        } else {
+            ""
+        };
+        format!("{}pub mod {} {{\n{}\n{}}}\n", attributes, module_name(&interface.name), header, body)
+    }
+}
+
+fn contains_word(text: &str, word: &str) -> bool {
+    text.match_indices(word).any(|(index, _)| {
+        let before = text[..index].chars().next_back();
+        let after = text[index + word.len()..].chars().next();
+        let boundary = |c: Option<char>| c.map(|c| !(c.is_alphanumeric() || c == '_')).unwrap_or(true);
+        boundary(before) && boundary(after)
+    })
+}
+
+struct Command {
+    name: String,
+    params: Vec<Member>,
+    ret: CType
+}
+
+fn methods(command: &Command, single_line: bool) -> String {
+    let args: Vec<String> = command.params.iter().map(field_name).collect();
+    let params: Vec<String> = command.params.iter().map(|param| format!("{}: {}", field_name(param), rust_type(&param.ty))).collect();
+    let ret = if command.ret.is_void() { None } else { Some(rust_type(&command.ret)) };
+    let mut out = Vec::new();
+    for &(prefix, macro_name) in &[("", "invoke_command"), ("try_", "try_invoke_command")] {
+        let ret = if prefix.is_empty() {
+            ret.clone()
+        } else {
+            Some(format!("Result<{}, NotLoaded>", ret.clone().unwrap_or_else(|| String::from("()"))))
+        };
+        let open = format!("{}pub unsafe fn {}{}(", IND.repeat(2), prefix, command.name);
+        // Clippy counts `&self` as well
+        let attribute = if params.len() + 1 > 7 {
+            format!("{}#[allow(clippy::too_many_arguments)]\n", IND.repeat(2))
+        } else {
+            String::new()
+        };
+        let mut signature = if single_line || params.is_empty() {
+            let mut all = vec![String::from("&self")];
+            all.extend(params.iter().cloned());
+            format!("{}{})", open, all.join(", "))
+        } else {
+            let pad = " ".repeat(open.len());
+            format!("{}&self, \n{}{})", open, pad, params.join(&format!(",\n{}", pad)))
+        };
+        if let Some(ret) = ret {
+            signature = signature + " -> " + &ret;
+        }
+        let call_args = if args.is_empty() { String::new() } else { format!(", {}", args.join(", ")) };
+        out.push(format!("{}{} {{\n{}{}!(self, {}{})\n{}}}\n", attribute, signature, IND.repeat(3), macro_name, command.name, call_args, IND.repeat(2)));
+    }
+    out.join("\n")
+}
+
+fn table_fields(commands: &[Command]) -> String {
+    commands.iter().map(|command| format!("{}{}: Command<{}Fn>", IND.repeat(2), command.name, command.name)).collect::<Vec<_>>().join(",\n")
+}
+
+fn load_lines(commands: &[&Command]) -> String {
+    commands.iter().map(|command| format!("{}self.{}.set(load_command!(self, \"{}\", report));", IND.repeat(4), command.name, command.name)).collect::<Vec<_>>().join("\n")
+}
+
+fn reset_lines(commands: &[&Command]) -> String {
+    commands.iter().map(|command| format!("{}self.{}.set(null);", IND.repeat(3), command.name)).collect::<Vec<_>>().join("\n")
+}
+
+fn introspection(commands: &[Command]) -> String {
+    let head = format!("{}command_introspection!(", IND.repeat(2));
+    let pad = " ".repeat(head.len());
+    let names: Vec<&str> = commands.iter().map(|command| command.name.as_str()).collect();
+    format!("{}{});\n", head, names.join(&format!(",\n{}", pad)))
+}
+
+fn table_methods(commands: &[Command], single_line: bool) -> String {
+    commands.iter().map(|command| format!("\n{}", methods(command, single_line))).collect()
+}
+
+fn core_table(commands: &[Command]) -> String {
+    let globals: Vec<&Command> = commands.iter().filter(|command| GLOBAL_COMMANDS.contains(&command.name.as_str())).collect();
+    let rest: Vec<&Command> = commands.iter().filter(|command| !GLOBAL_COMMANDS.contains(&command.name.as_str()) && command.name != "vkGetInstanceProcAddr").collect();
+    let global_lines: Vec<String> = globals.iter().map(|command| format!("{}vulkan_core.{}.set(load_command!(vulkan_core, \"{}\", report));", IND.repeat(4), command.name, command.name)).collect();
+    format!(r#"    pub struct VkCoreCommands {{
+        library: Option<Arc<DynamicLibrary>>,
+        instance: VkInstance,
+{fields}
+    }}
+
+    // The stored handle is only passed to vkGet*ProcAddr and the command slots are atomic
+    unsafe impl Send for VkCoreCommands {{}}
+    unsafe impl Sync for VkCoreCommands {{}}
+
+    impl VkCoreCommands {{
+        pub fn new() -> Result<VkCoreCommands, LoadError> {{
+            VkCoreCommands::with_config(&LoaderConfig::new())
+        }}
+
+        pub fn with_config(config: &LoaderConfig) -> Result<VkCoreCommands, LoadError> {{
+            let entry = Entry::with_config(config)?;
+            VkCoreCommands::from_entry(&entry)
+        }}
+
+        /// Creates the command table from a caller-supplied vkGetInstanceProcAddr, only the global commands are loaded
+        ///
+        /// # Safety
+        ///
+        /// See `Entry::from_proc_addr` for the requirements on `vkGetInstanceProcAddr`.
+        pub unsafe fn from_proc_addr(vkGetInstanceProcAddr: vkGetInstanceProcAddrFn) -> Result<VkCoreCommands, LoadError> {{
+            VkCoreCommands::from_entry(&Entry::from_proc_addr(vkGetInstanceProcAddr))
+        }}
+
+        /// Creates the command table from a shared entry, only the global commands are loaded
+        pub fn from_entry(entry: &Entry) -> Result<VkCoreCommands, LoadError> {{
+            unsafe {{
+                let mut vulkan_core: VkCoreCommands;
+                vulkan_core = ::std::mem::zeroed::<VkCoreCommands>();
+                vulkan_core.library = entry.library().cloned();
+                vulkan_core.vkGetInstanceProcAddr.set(entry.get_instance_proc_addr() as vkVoidFunctionFn);
+                // Load global commands via vkGetInstanceProcAddr
+                let mut report = LoadReport::new();
+{globals}
+                match report.missing.first() {{
+                    Some(&name) => Err(LoadError::CommandMissing {{ name, instance: VkInstance::null() }}),
+                    None => Ok(vulkan_core)
+                }}
+            }}
+        }}
+
+        /// Loads all commands for the instance, failing on the first command that is missing
+        pub fn load(&mut self, instance: VkInstance) -> Result<(), LoadError> {{
+            let report = self.load_lenient(instance);
+            match report.missing.first() {{
+                Some(&name) => Err(LoadError::CommandMissing {{ name, instance }}),
+                None => Ok(())
+            }}
+        }}
+
+        /// Tries to load every command for the instance, the commands that are missing are left unloaded and listed in the report
+        pub fn load_lenient(&mut self, instance: VkInstance) -> LoadReport {{
+            let mut report = LoadReport::new();
+            self.instance = instance;
+            unsafe {{
+                // Keep the current vkGetInstanceProcAddr if the driver doesn't return one for the instance
+                let fn_ptr = load_command!(self, "vkGetInstanceProcAddr", report);
+                if !fn_ptr.is_null() {{
+                    self.vkGetInstanceProcAddr.set(fn_ptr);
+                }}
+{loads}
+            }}
+            report
+        }}
+
+        /// Records the instance without loading any command, each command is resolved on its first call
+        pub fn load_lazy(&mut self, instance: VkInstance) {{
+            self.instance = instance;
+        }}
+
+        /// Resolves a command through the stored vkGetInstanceProcAddr for the recorded instance
+        unsafe fn resolve_command(&self, name: *const c_char) -> vkVoidFunctionFn {{
+            match self.vkGetInstanceProcAddr.get() {{
+                Some(vkGetInstanceProcAddr) => vkGetInstanceProcAddr(self.instance, name),
+                None => ::std::ptr::null()
+            }}
+        }}
+
+{introspection}    }}
+
+    // Each wrapper has the safety requirements of the Vulkan command it calls
+    #[allow(clippy::missing_safety_doc)]
+    impl VkCoreCommands {{{methods}    }}
+"#, fields = table_fields(commands), globals = global_lines.join("\n"), loads = load_lines(&rest),
+        introspection = introspection(commands), methods = table_methods(commands, true))
+}
+
+fn versioned_table(interface: &str, commands: &[Command]) -> String {
+    let table = table_name(interface);
+    let var = format!("vulkan_{}", module_name(interface));
+    let version = interface.trim_start_matches("VK_VERSION_");
+    let version_const = format!("VK_API_VERSION_{}", version);
+    let version_text = format!("Vulkan {}", version.replace('_', "."));
+    let globals: Vec<&Command> = commands.iter().filter(|command| GLOBAL_COMMANDS.contains(&command.name.as_str())).collect();
+    let rest: Vec<&Command> = commands.iter().filter(|command| !GLOBAL_COMMANDS.contains(&command.name.as_str())).collect();
+    let mut global_lines = String::new();
+    if !globals.is_empty() {
+        global_lines.push_str("                // Global commands are loaded without an instance, they are missing from older loaders\n");
+        for command in &globals {
+            let _ = writeln!(global_lines, "{}{}.{}.set(entry.vkGetInstanceProcAddr(VkInstance::null(), b\"{}\\0\".as_ptr() as *const c_char));", IND.repeat(4), var, command.name, command.name);
+        }
+    }
+    format!(r#"    pub struct {table} {{
+        library: Option<Arc<DynamicLibrary>>,
+        instance: VkInstance,
+        api_version: uint32_t,
+        vkGetInstanceProcAddr: Command<vkGetInstanceProcAddrFn>,
+{fields}
+    }}
+
+    // The stored handle is only passed to vkGet*ProcAddr and the command slots are atomic
+    unsafe impl Send for {table} {{}}
+    unsafe impl Sync for {table} {{}}
+
+    impl {table} {{
+        pub fn new() -> Result<{table}, LoadError> {{
+            {table}::with_config(&LoaderConfig::new())
+        }}
+
+        pub fn with_config(config: &LoaderConfig) -> Result<{table}, LoadError> {{
+            let entry = Entry::with_config(config)?;
+            Ok({table}::unloaded(&entry))
+        }}
+
+        /// Creates the command table from a caller-supplied vkGetInstanceProcAddr and loads it for an instance created with `api_version`
+        ///
+        /// # Safety
+        ///
+        /// See `Entry::from_proc_addr` for the requirements on `vkGetInstanceProcAddr`.
+        pub unsafe fn from_proc_addr(vkGetInstanceProcAddr: vkGetInstanceProcAddrFn, instance: VkInstance, api_version: uint32_t) -> Result<{table}, LoadError> {{
+            {table}::from_entry(&Entry::from_proc_addr(vkGetInstanceProcAddr), instance, api_version)
+        }}
+
+        /// Creates the command table from a shared entry and loads it for an instance created with `api_version`
+        pub fn from_entry(entry: &Entry, instance: VkInstance, api_version: uint32_t) -> Result<{table}, LoadError> {{
+            let mut {var} = {table}::unloaded(entry);
+            {var}.load(instance, api_version)?;
+            Ok({var})
+        }}
+
+        /// Creates the command table from a shared entry and loads every command the driver provides for an instance created with `api_version`
+        pub fn from_entry_lenient(entry: &Entry, instance: VkInstance, api_version: uint32_t) -> ({table}, LoadReport) {{
+            let mut {var} = {table}::unloaded(entry);
+            let report = {var}.load_lenient(instance, api_version);
+            ({var}, report)
+        }}
+
+        /// Creates the command table from a shared entry for an instance created with `api_version`, commands are resolved on their first call
+        pub fn from_entry_lazy(entry: &Entry, instance: VkInstance, api_version: uint32_t) -> {table} {{
+            let mut {var} = {table}::unloaded(entry);
+            {var}.load_lazy(instance, api_version);
+            {var}
+        }}
+
+        fn unloaded(entry: &Entry) -> {table} {{
+            unsafe {{
+                let mut {var}: {table} = ::std::mem::zeroed::<{table}>();
+                {var}.library = entry.library().cloned();
+                {var}.vkGetInstanceProcAddr.set(entry.get_instance_proc_addr() as vkVoidFunctionFn);
+{globals}                {var}
+            }}
+        }}
+
+        /// Loads all commands for an instance created with `api_version`, failing if it is older than {version_text} or on the first command that is missing
+        pub fn load(&mut self, instance: VkInstance, api_version: uint32_t) -> Result<(), LoadError> {{
+            if api_version < {version_const} {{
+                return Err(LoadError::VersionNotSupported {{ required: {version_const}, version: api_version }});
+            }}
+            let report = self.load_lenient(instance, api_version);
+            match report.missing.first() {{
+                Some(&name) => Err(LoadError::CommandMissing {{ name, instance }}),
+                None => Ok(())
+            }}
+        }}
+
+        /// Tries to load every command for an instance created with `api_version`, the commands that are missing are left unloaded and listed in the report
+        ///
+        /// Commands of a previously loaded instance are unloaded. Nothing is loaded for instances older than {version_text} and the report is empty, as none of the commands are expected.
+        pub fn load_lenient(&mut self, instance: VkInstance, api_version: uint32_t) -> LoadReport {{
+            let mut report = LoadReport::new();
+            self.instance = instance;
+            self.api_version = api_version;
+            self.reset_commands();
+            if api_version < {version_const} {{
+                return report;
+            }}
+            unsafe {{
+{loads}
+            }}
+            report
+        }}
+
+        /// Records the instance and its API version and unloads the commands of a previous instance, each command is resolved on its first call
+        pub fn load_lazy(&mut self, instance: VkInstance, api_version: uint32_t) {{
+            self.instance = instance;
+            self.api_version = api_version;
+            self.reset_commands();
+        }}
+
+        /// Unloads the commands of the previous instance, the global commands stay loaded
+        fn reset_commands(&self) {{
+            let null = ::std::ptr::null();
+{resets}
+        }}
+
+        /// Resolves a command through the stored vkGetInstanceProcAddr, nothing is resolved for instances older than {version_text}
+        unsafe fn resolve_command(&self, name: *const c_char) -> vkVoidFunctionFn {{
+            match self.vkGetInstanceProcAddr.get() {{
+                Some(vkGetInstanceProcAddr) if self.api_version >= {version_const} => vkGetInstanceProcAddr(self.instance, name),
+                _ => ::std::ptr::null()
+            }}
+        }}
+
+{introspection}    }}
+
+    // Each wrapper has the safety requirements of the Vulkan command it calls
+    #[allow(clippy::missing_safety_doc)]
+    impl {table} {{{methods}    }}
+"#, table = table, var = var, fields = table_fields(commands), globals = global_lines, version_text = version_text,
+        version_const = version_const, loads = load_lines(&rest), resets = reset_lines(&rest), introspection = introspection(commands),
+        methods = table_methods(commands, true))
+}
+
+fn extension_table(interface: &str, commands: &[Command]) -> String {
+    let table = table_name(interface);
+    let var = format!("vulkan_{}", module_name(interface));
+    let all: Vec<&Command> = commands.iter().collect();
+    format!(r#"    pub struct {table} {{
+        library: Option<Arc<DynamicLibrary>>,
+        instance: VkInstance,
+        vkGetInstanceProcAddr: Command<vkGetInstanceProcAddrFn>,
+{fields}
+    }}
+
+    // The stored handle is only passed to vkGet*ProcAddr and the command slots are atomic
+    unsafe impl Send for {table} {{}}
+    unsafe impl Sync for {table} {{}}
+
+    impl {table} {{
+        pub fn new() -> Result<{table}, LoadError> {{
+            {table}::with_config(&LoaderConfig::new())
+        }}
+
+        pub fn with_config(config: &LoaderConfig) -> Result<{table}, LoadError> {{
+            let entry = Entry::with_config(config)?;
+            Ok({table}::unloaded(&entry))
+        }}
+
+        /// Creates the command table from a caller-supplied vkGetInstanceProcAddr and loads it for the instance
+        ///
+        /// # Safety
+        ///
+        /// See `Entry::from_proc_addr` for the requirements on `vkGetInstanceProcAddr`.
+        pub unsafe fn from_proc_addr(vkGetInstanceProcAddr: vkGetInstanceProcAddrFn, instance: VkInstance) -> Result<{table}, LoadError> {{
+            {table}::from_entry(&Entry::from_proc_addr(vkGetInstanceProcAddr), instance)
+        }}
+
+        /// Creates the command table from a shared entry and loads it for the instance
+        pub fn from_entry(entry: &Entry, instance: VkInstance) -> Result<{table}, LoadError> {{
+            let mut {var} = {table}::unloaded(entry);
+            {var}.load(instance)?;
+            Ok({var})
+        }}
+
+        /// Creates the command table from a shared entry and loads every command the driver provides for the instance
+        pub fn from_entry_lenient(entry: &Entry, instance: VkInstance) -> ({table}, LoadReport) {{
+            let mut {var} = {table}::unloaded(entry);
+            let report = {var}.load_lenient(instance);
+            ({var}, report)
+        }}
+
+        /// Creates the command table from a shared entry for the instance, commands are resolved on their first call
+        pub fn from_entry_lazy(entry: &Entry, instance: VkInstance) -> {table} {{
+            let mut {var} = {table}::unloaded(entry);
+            {var}.load_lazy(instance);
+            {var}
+        }}
+
+        fn unloaded(entry: &Entry) -> {table} {{
+            unsafe {{
+                let mut {var}: {table} = ::std::mem::zeroed::<{table}>();
+                {var}.library = entry.library().cloned();
+                {var}.vkGetInstanceProcAddr.set(entry.get_instance_proc_addr() as vkVoidFunctionFn);
+                {var}
+            }}
+        }}
+
+        /// Loads all commands for the instance, failing on the first command that is missing
+        pub fn load(&mut self, instance: VkInstance) -> Result<(), LoadError> {{
+            let report = self.load_lenient(instance);
+            match report.missing.first() {{
+                Some(&name) => Err(LoadError::CommandMissing {{ name, instance }}),
+                None => Ok(())
+            }}
+        }}
+
+        /// Tries to load every command for the instance, the commands that are missing are left unloaded and listed in the report
+        pub fn load_lenient(&mut self, instance: VkInstance) -> LoadReport {{
+            let mut report = LoadReport::new();
+            self.instance = instance;
+            unsafe {{
+{loads}
+            }}
+            report
+        }}
+
+        /// Records the instance without loading any command, each command is resolved on its first call
+        pub fn load_lazy(&mut self, instance: VkInstance) {{
+            self.instance = instance;
+        }}
+
+        /// Resolves a command through the stored vkGetInstanceProcAddr for the recorded instance
+        unsafe fn resolve_command(&self, name: *const c_char) -> vkVoidFunctionFn {{
+            match self.vkGetInstanceProcAddr.get() {{
+                Some(vkGetInstanceProcAddr) => vkGetInstanceProcAddr(self.instance, name),
+                None => ::std::ptr::null()
+            }}
+        }}
+
+{introspection}    }}
+
+    // Each wrapper has the safety requirements of the Vulkan command it calls
+    #[allow(clippy::missing_safety_doc)]
+    impl {table} {{{methods}    }}
+"#, table = table, var = var, fields = table_fields(commands), loads = load_lines(&all),
+        introspection = introspection(commands), methods = table_methods(commands, false))
+}
+
+/// Generates one module per core version and per extension in `extensions`
+///
+/// Extensions are emitted in registry order after the core versions.
+pub fn generate(registry: &Registry, extensions: &[&str]) -> Result<Vec<Module>, GeneratorError> {
+    let mut interfaces: Vec<&Interface> = registry.features.iter().collect();
+    for name in extensions {
+        if registry.extensions.iter().all(|extension| extension.name != *name) {
+            return Err(GeneratorError::UnknownInterface(name.to_string()));
+        }
+    }
+    interfaces.extend(registry.extensions.iter().filter(|extension| extensions.contains(&extension.name.as_str())));
+    let mut array_constants = HashSet::new();
+    for def in &registry.types {
+        if let TypeKind::Struct { ref members, .. } | TypeKind::Union { ref members } = def.kind {
+            for member in members {
+                array_constants.extend(member.ty.array.iter().filter(|dimension| super::registry::parse_int(dimension).is_none()).cloned());
+            }
+        }
+    }
+    let mut generator = Generator {
+        registry,
+        contents: interfaces.iter().map(|_| Contents::default()).collect(),
+        interfaces,
+        owner: HashMap::new(),
+        extra_values: HashMap::new(),
+        array_constants,
+        constant_types: HashMap::new()
+    };
+    generator.collect();
+    let mut constant_types = HashMap::new();
+    for contents in &generator.contents {
+        for (name, value) in &contents.extension_constants {
+            match *value {
+                Value::Int(_) => constant_types.insert(name.clone(), String::from("uint32_t")),
+                Value::Str(_) => constant_types.insert(name.clone(), String::from("*const c_char")),
+                _ => None
+            };
+        }
+    }
+    generator.constant_types = constant_types;
+    Ok((0..generator.interfaces.len()).map(|module| Module {
+        name: module_name(&generator.interfaces[module].name),
+        interface: generator.interfaces[module].name.clone(),
+        text: generator.module_text(module)
+    }).collect())
+}
+
+/// Joins the modules into one file
+pub fn render(modules: &[Module]) -> String {
+    let mut text = String::from("// Generated by dvk-generator from vk.xml\n");
+    for module in modules {
+        text.push('\n');
+        text.push_str(&module.text);
+    }
+    text
+}
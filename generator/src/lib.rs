@@ -0,0 +1,47 @@
+//! Generates the dvk bindings from the Khronos vk.xml registry
+//!
+//! The registry is parsed into `registry::Registry`, `emit` turns it into modules formatted like
+//! the hand-written `src/lib.rs` and `report` lists the differences between the two.
+
+extern crate roxmltree;
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+pub mod registry;
+pub mod emit;
+pub mod report;
+
+/// Vulkan-Docs tag of the vendored registry, `registry/fetch.sh` downloads it and checks it against `registry/vk.xml.sha256`
+pub const REGISTRY_VERSION: &str = "v1.3.281";
+
+/// The vendored registry, the default input of `dvk-generator`
+pub const REGISTRY_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/registry/vk.xml");
+
+#[derive(Debug)]
+pub enum GeneratorError {
+    Io(io::Error),
+    /// vk.xml is not well-formed or is not a registry
+    Xml(String),
+    /// An extension passed on the command line is not in the registry
+    UnknownInterface(String)
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GeneratorError::Io(ref error) => write!(f, "{}", error),
+            GeneratorError::Xml(ref message) => write!(f, "invalid registry: {}", message),
+            GeneratorError::UnknownInterface(ref name) => write!(f, "{} is not in the registry", name)
+        }
+    }
+}
+
+impl Error for GeneratorError {}
+
+impl From<io::Error> for GeneratorError {
+    fn from(error: io::Error) -> GeneratorError {
+        GeneratorError::Io(error)
+    }
+}
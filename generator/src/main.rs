@@ -0,0 +1,102 @@
+//! Generates dvk modules from vk.xml and reports how they differ from `src/lib.rs`
+//!
+//! Usage: dvk-generator [<vk.xml>] [--lib <src/lib.rs>] [--out <file>] [--report <file>] [--extension <name>]...
+//!
+//! Without a path the vendored `generator/registry/vk.xml` is read.
+//! The extensions `src/lib.rs` has modules for are always generated, `--extension` adds more.
+//! The report is printed when no `--report` file is given.
+
+extern crate dvk_generator;
+
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process;
+use dvk_generator::{GeneratorError, REGISTRY_PATH};
+use dvk_generator::registry::Registry;
+use dvk_generator::emit::{generate, render, HAND_WRITTEN_EXTENSIONS};
+use dvk_generator::report::Report;
+
+const USAGE: &str = "usage: dvk-generator [<vk.xml>] [--lib <src/lib.rs>] [--out <file>] [--report <file>] [--extension <name>]...";
+
+struct Options {
+    registry: String,
+    lib: String,
+    out: Option<String>,
+    report: Option<String>,
+    extensions: Vec<String>
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut args = env::args().skip(1);
+    let mut options = Options {
+        registry: String::new(),
+        lib: String::from("src/lib.rs"),
+        out: None,
+        report: None,
+        extensions: Vec::new()
+    };
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("{} needs a value", flag));
+        match arg.as_str() {
+            "--lib" => options.lib = value("--lib")?,
+            "--out" => options.out = Some(value("--out")?),
+            "--report" => options.report = Some(value("--report")?),
+            "--extension" => options.extensions.push(value("--extension")?),
+            _ if arg.starts_with("--") || !options.registry.is_empty() => return Err(format!("unexpected argument {}", arg)),
+            _ => options.registry = arg
+        }
+    }
+    if options.registry.is_empty() {
+        if !Path::new(REGISTRY_PATH).exists() {
+            return Err(format!("{} is missing, run generator/registry/fetch.sh or pass the path to vk.xml", REGISTRY_PATH));
+        }
+        options.registry = String::from(REGISTRY_PATH);
+    }
+    Ok(options)
+}
+
+fn read(path: &str) -> Result<String, GeneratorError> {
+    let mut text = String::new();
+    File::open(path)?.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+fn write(path: &str, text: &str) -> Result<(), GeneratorError> {
+    File::create(path)?.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+fn run(options: &Options) -> Result<(), GeneratorError> {
+    let registry = Registry::parse(&read(&options.registry)?)?;
+    // Registries older than an extension that src/lib.rs covers simply don't generate its module
+    let mut extensions: Vec<&str> = HAND_WRITTEN_EXTENSIONS.iter().cloned().filter(|&name| registry.interface(name).is_some()).collect();
+    extensions.extend(options.extensions.iter().map(|name| name.as_str()));
+    let generated = render(&generate(&registry, &extensions)?);
+    if let Some(ref out) = options.out {
+        write(out, &generated)?;
+    }
+    let report = Report::new(&generated, &read(&options.lib)?).to_text();
+    match options.report {
+        Some(ref path) => write(path, &report),
+        None => {
+            print!("{}", report);
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(error) = run(&options) {
+        eprintln!("dvk-generator: {}", error);
+        process::exit(1);
+    }
+}
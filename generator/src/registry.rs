@@ -0,0 +1,502 @@
+//! The parts of the Khronos vk.xml registry the generator needs
+
+use ::std::collections::HashMap;
+use ::roxmltree::{Document, Node};
+use ::GeneratorError;
+
+/// A C type as written in the registry, e.g. `const char* const*` or `float[4]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CType {
+    pub name: String,
+    /// One entry per pointer level starting from the outermost, true if the pointee is const
+    pub pointers: Vec<bool>,
+    /// Array dimensions starting from the outermost
+    pub array: Vec<String>,
+    pub bitfield: Option<u32>
+}
+
+impl CType {
+    pub fn named(name: &str) -> CType {
+        CType {
+            name: name.to_string(),
+            pointers: Vec::new(),
+            array: Vec::new(),
+            bitfield: None
+        }
+    }
+
+    pub fn is_void(&self) -> bool {
+        self.name == "void" && self.pointers.is_empty()
+    }
+}
+
+/// A struct member, command parameter or function pointer parameter
+#[derive(Clone, Debug)]
+pub struct Member {
+    pub name: String,
+    pub ty: CType,
+    /// The `values` attribute, only set for sType members
+    pub values: Option<String>
+}
+
+#[derive(Clone, Debug)]
+pub enum TypeKind {
+    /// `typedef uint32_t VkFlags;`, the type is None for opaque platform structs
+    BaseType(Option<CType>),
+    /// A `*Flags` typedef and the name of its `*FlagBits` enum
+    Bitmask { bits: Option<String>, width64: bool },
+    Handle { dispatchable: bool },
+    Enum,
+    Struct { members: Vec<Member>, returned_only: bool },
+    Union { members: Vec<Member> },
+    FuncPointer { ret: CType, params: Vec<Member> },
+    /// Includes, defines and types coming from platform headers
+    Other
+}
+
+#[derive(Clone, Debug)]
+pub struct TypeDef {
+    pub name: String,
+    pub kind: TypeKind,
+    pub alias: Option<String>
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(String),
+    Str(String),
+    /// C expressions such as `(~0U)`
+    Expr(String),
+    Alias(String)
+}
+
+#[derive(Clone, Debug)]
+pub struct EnumValue {
+    pub name: String,
+    pub value: Value,
+    /// The `type` attribute of API constants
+    pub ty: Option<String>
+}
+
+/// An `<enums>` block, either a C enum or the bits of a `*FlagBits` type
+#[derive(Clone, Debug)]
+pub struct EnumGroup {
+    pub name: String,
+    pub bitmask: bool,
+    pub width64: bool,
+    pub values: Vec<EnumValue>
+}
+
+#[derive(Clone, Debug)]
+pub struct Command {
+    pub name: String,
+    pub ret: CType,
+    pub params: Vec<Member>,
+    pub alias: Option<String>
+}
+
+/// An enum named by a `<require>` block, values are only set for enums the block defines
+#[derive(Clone, Debug)]
+pub struct RequiredEnum {
+    pub name: String,
+    pub extends: Option<String>,
+    pub value: Option<Value>
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Require {
+    pub types: Vec<String>,
+    pub commands: Vec<String>,
+    pub enums: Vec<RequiredEnum>
+}
+
+/// A core version (`<feature>`) or an extension
+#[derive(Clone, Debug)]
+pub struct Interface {
+    pub name: String,
+    pub number: u32,
+    pub is_extension: bool,
+    pub requires: Vec<Require>
+}
+
+pub struct Registry {
+    pub types: Vec<TypeDef>,
+    pub enum_groups: Vec<EnumGroup>,
+    pub constants: Vec<EnumValue>,
+    pub commands: Vec<Command>,
+    pub features: Vec<Interface>,
+    pub extensions: Vec<Interface>,
+    type_index: HashMap<String, usize>,
+    group_index: HashMap<String, usize>,
+    command_index: HashMap<String, usize>
+}
+
+/// Checks the `api` attribute, elements meant only for Vulkan SC are skipped
+fn for_vulkan(node: &Node) -> bool {
+    match node.attribute("api").or_else(|| node.attribute("supported")) {
+        Some(apis) => apis.split(',').any(|api| api == "vulkan"),
+        None => true
+    }
+}
+
+fn child_text(node: &Node, tag: &str) -> Option<String> {
+    node.children()
+        .find(|child| child.is_element() && child.has_tag_name(tag))
+        .and_then(|child| child.text())
+        .map(|text| text.to_string())
+}
+
+/// Parses an integer written the way the registry writes them
+pub fn parse_int(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text)
+    };
+    let value = if digits.starts_with("0x") || digits.starts_with("0X") {
+        i64::from_str_radix(&digits[2..], 16).ok()
+    } else {
+        digits.parse::<i64>().ok()
+    };
+    value.map(|value| if negative { -value } else { value })
+}
+
+fn parse_value(text: &str) -> Value {
+    if text.starts_with('"') {
+        return Value::Str(text.trim_matches('"').to_string());
+    }
+    if let Some(value) = parse_int(text) {
+        return Value::Int(value);
+    }
+    let float = text.trim_end_matches('F').trim_end_matches('f');
+    if float.contains('.') && float.parse::<f64>().is_ok() {
+        return Value::Float(float.to_string());
+    }
+    Value::Expr(text.to_string())
+}
+
+/// Value of an enum defined inline in an extension or feature
+fn enum_value(node: &Node, extension_number: Option<u32>) -> Option<Value> {
+    if let Some(alias) = node.attribute("alias") {
+        return Some(Value::Alias(alias.to_string()));
+    }
+    if let Some(bitpos) = node.attribute("bitpos") {
+        return parse_int(bitpos).map(|bitpos| Value::Int(1i64 << bitpos));
+    }
+    if let Some(offset) = node.attribute("offset") {
+        let number = node.attribute("extnumber").and_then(parse_int).map(|number| number as u32).or(extension_number);
+        let value = 1000000000 + (number.unwrap_or(1) as i64 - 1) * 1000 + parse_int(offset).unwrap_or(0);
+        return Some(Value::Int(if node.attribute("dir") == Some("-") { -value } else { value }));
+    }
+    node.attribute("value").map(parse_value)
+}
+
+/// Splits the text around a member's `<type>` and `<name>` into pointers, arrays and bitfields
+fn member(node: &Node) -> Option<Member> {
+    let mut ty = None;
+    let mut name = None;
+    let mut before_type = String::new();
+    let mut after_type = String::new();
+    let mut after_name = String::new();
+    for child in node.children() {
+        if child.is_element() {
+            match child.tag_name().name() {
+                "type" => ty = child.text().map(|text| text.to_string()),
+                "name" => name = child.text().map(|text| text.to_string()),
+                "enum" => after_name.push_str(child.text().unwrap_or("")),
+                _ => {}
+            }
+        } else if let Some(text) = child.text() {
+            if name.is_some() {
+                after_name.push_str(text);
+            } else if ty.is_some() {
+                after_type.push_str(text);
+            } else {
+                before_type.push_str(text);
+            }
+        }
+    }
+    let mut ty = CType::named(&try_opt(ty));
+    let mut pending_const = before_type.contains("const");
+    let mut innermost_first = Vec::new();
+    for token in after_type.replace('*', " * ").split_whitespace() {
+        match token {
+            "const" => pending_const = true,
+            "*" => {
+                innermost_first.push(pending_const);
+                pending_const = false;
+            },
+            _ => {}
+        }
+    }
+    innermost_first.reverse();
+    ty.pointers = innermost_first;
+    let mut rest = after_name.trim();
+    while rest.starts_with('[') {
+        let end = rest.find(']').unwrap_or(rest.len() - 1);
+        ty.array.push(rest[1..end].trim().to_string());
+        rest = rest[end + 1..].trim();
+    }
+    if let Some(bits) = rest.strip_prefix(':') {
+        ty.bitfield = parse_int(bits).map(|bits| bits as u32);
+    }
+    Some(Member {
+        name: try_opt(name),
+        ty,
+        values: node.attribute("values").map(|values| values.to_string())
+    })
+}
+
+fn try_opt(value: Option<String>) -> String {
+    value.unwrap_or_default()
+}
+
+/// Parses a parameter written as plain C text, used by function pointers in older registries
+fn text_param(text: &str) -> Option<Member> {
+    let text = text.trim();
+    if text.is_empty() || text == "void" {
+        return None;
+    }
+    let spaced = text.replace('*', " * ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+    let (name, type_tokens) = tokens.split_last().unwrap();
+    let mut ty = None;
+    let mut pending_const = false;
+    let mut innermost_first = Vec::new();
+    for token in type_tokens {
+        match *token {
+            "const" => pending_const = true,
+            "struct" => {},
+            "*" => {
+                innermost_first.push(pending_const);
+                pending_const = false;
+            },
+            other => {
+                ty = Some(other.to_string());
+            }
+        }
+    }
+    let mut ty = CType::named(&try_opt(ty));
+    innermost_first.reverse();
+    ty.pointers = innermost_first;
+    Some(Member { name: name.to_string(), ty, values: None })
+}
+
+fn func_pointer(node: &Node) -> (String, CType, Vec<Member>) {
+    // Newer registries describe function pointers with <proto> and <param> like commands
+    if let Some(proto) = node.children().find(|child| child.has_tag_name("proto")) {
+        let proto = member(&proto).unwrap();
+        let params = node.children().filter(|child| child.has_tag_name("param")).filter_map(|child| member(&child)).collect();
+        return (proto.name, proto.ty, params);
+    }
+    let text: String = node.descendants().filter(|child| child.is_text()).map(|child| child.text().unwrap_or("")).collect();
+    let name = child_text(node, "name").unwrap_or_default();
+    let ret_text = text.trim_start_matches("typedef").split("(VKAPI_PTR").next().unwrap_or("void");
+    let ret = text_param(&format!("{} ret", ret_text)).map(|ret| ret.ty).unwrap_or_else(|| CType::named("void"));
+    let params_text = text.split_once(")(").map(|(_, params)| params).unwrap_or("").trim_end().trim_end_matches(';').trim_end_matches(')');
+    let params = params_text.split(',').filter_map(text_param).collect();
+    (name, ret, params)
+}
+
+fn type_def(node: &Node) -> Option<TypeDef> {
+    let category = node.attribute("category").unwrap_or("");
+    let name = node.attribute("name").map(|name| name.to_string()).or_else(|| child_text(node, "name"));
+    let name = name?;
+    let alias = node.attribute("alias").map(|alias| alias.to_string());
+    let kind = if alias.is_some() {
+        TypeKind::Other
+    } else {
+        match category {
+            "basetype" => TypeKind::BaseType(node.children().find(|child| child.has_tag_name("type")).and_then(|_| member(node)).map(|member| member.ty)),
+            "bitmask" => TypeKind::Bitmask {
+                bits: node.attribute("requires").or_else(|| node.attribute("bitvalues")).map(|bits| bits.to_string()),
+                width64: child_text(node, "type").map(|ty| ty == "VkFlags64").unwrap_or(false)
+            },
+            "handle" => TypeKind::Handle {
+                dispatchable: child_text(node, "type").map(|ty| ty == "VK_DEFINE_HANDLE").unwrap_or(false)
+            },
+            "enum" => TypeKind::Enum,
+            "struct" | "union" => {
+                let members = node.children()
+                    .filter(|child| child.has_tag_name("member") && for_vulkan(child))
+                    .filter_map(|child| member(&child))
+                    .collect();
+                if category == "struct" {
+                    TypeKind::Struct { members, returned_only: node.attribute("returnedonly") == Some("true") }
+                } else {
+                    TypeKind::Union { members }
+                }
+            },
+            "funcpointer" => {
+                let (_, ret, params) = func_pointer(node);
+                TypeKind::FuncPointer { ret, params }
+            },
+            _ => TypeKind::Other
+        }
+    };
+    Some(TypeDef { name, kind, alias })
+}
+
+fn command(node: &Node) -> Option<Command> {
+    if let Some(alias) = node.attribute("alias") {
+        return Some(Command {
+            name: node.attribute("name").unwrap_or("").to_string(),
+            ret: CType::named("void"),
+            params: Vec::new(),
+            alias: Some(alias.to_string())
+        });
+    }
+    let proto = node.children().find(|child| child.has_tag_name("proto")).and_then(|proto| member(&proto))?;
+    let params = node.children()
+        .filter(|child| child.has_tag_name("param") && for_vulkan(child))
+        .filter_map(|child| member(&child))
+        .collect();
+    Some(Command { name: proto.name, ret: proto.ty, params, alias: None })
+}
+
+fn interface(node: &Node, is_extension: bool) -> Interface {
+    let number = node.attribute("number").map(|number| {
+        // Features are numbered "1.2", extensions by their registry number
+        if is_extension {
+            parse_int(number).unwrap_or(0) as u32
+        } else {
+            let mut parts = number.split('.').map(|part| parse_int(part).unwrap_or(0) as u32);
+            let major = parts.next().unwrap_or(1);
+            let minor = parts.next().unwrap_or(0);
+            major * 100 + minor
+        }
+    }).unwrap_or(0);
+    let extension_number = if is_extension { Some(number) } else { None };
+    let mut requires = Vec::new();
+    for block in node.children().filter(|child| child.has_tag_name("require") && for_vulkan(child)) {
+        let mut require = Require::default();
+        for item in block.children().filter(|child| child.is_element() && for_vulkan(child)) {
+            let name = item.attribute("name").unwrap_or("").to_string();
+            match item.tag_name().name() {
+                "type" => require.types.push(name),
+                "command" => require.commands.push(name),
+                "enum" => require.enums.push(RequiredEnum {
+                    name,
+                    extends: item.attribute("extends").map(|extends| extends.to_string()),
+                    value: enum_value(&item, extension_number)
+                }),
+                _ => {}
+            }
+        }
+        requires.push(require);
+    }
+    Interface {
+        name: node.attribute("name").unwrap_or("").to_string(),
+        number,
+        is_extension,
+        requires
+    }
+}
+
+impl Registry {
+    /// Parses the text of vk.xml
+    pub fn parse(xml: &str) -> Result<Registry, GeneratorError> {
+        let document = Document::parse(xml).map_err(|error| GeneratorError::Xml(error.to_string()))?;
+        let root = document.root_element();
+        if !root.has_tag_name("registry") {
+            return Err(GeneratorError::Xml(String::from("the root element is not <registry>")));
+        }
+        let mut registry = Registry {
+            types: Vec::new(),
+            enum_groups: Vec::new(),
+            constants: Vec::new(),
+            commands: Vec::new(),
+            features: Vec::new(),
+            extensions: Vec::new(),
+            type_index: HashMap::new(),
+            group_index: HashMap::new(),
+            command_index: HashMap::new()
+        };
+        for section in root.children().filter(|child| child.is_element()) {
+            match section.tag_name().name() {
+                "types" => {
+                    for node in section.children().filter(|child| child.has_tag_name("type") && for_vulkan(child)) {
+                        if let Some(def) = type_def(&node) {
+                            registry.types.push(def);
+                        }
+                    }
+                },
+                "enums" => {
+                    let name = section.attribute("name").unwrap_or("").to_string();
+                    let values: Vec<EnumValue> = section.children()
+                        .filter(|child| child.has_tag_name("enum") && for_vulkan(child))
+                        .filter_map(|child| enum_value(&child, None).map(|value| EnumValue {
+                            name: child.attribute("name").unwrap_or("").to_string(),
+                            value,
+                            ty: child.attribute("type").map(|ty| ty.to_string())
+                        }))
+                        .collect();
+                    match section.attribute("type") {
+                        Some(kind) => registry.enum_groups.push(EnumGroup {
+                            name,
+                            bitmask: kind == "bitmask",
+                            width64: section.attribute("bitwidth") == Some("64"),
+                            values
+                        }),
+                        None => registry.constants.extend(values)
+                    }
+                },
+                "commands" => {
+                    for node in section.children().filter(|child| child.has_tag_name("command") && for_vulkan(child)) {
+                        if let Some(command) = command(&node) {
+                            registry.commands.push(command);
+                        }
+                    }
+                },
+                "feature" if for_vulkan(&section) => registry.features.push(interface(&section, false)),
+                "extensions" => {
+                    for node in section.children().filter(|child| child.has_tag_name("extension")) {
+                        if for_vulkan(&node) && node.attribute("supported") != Some("disabled") {
+                            registry.extensions.push(interface(&node, true));
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+        registry.features.sort_by_key(|feature| feature.number);
+        registry.extensions.sort_by_key(|extension| extension.number);
+        for (index, def) in registry.types.iter().enumerate() {
+            registry.type_index.entry(def.name.clone()).or_insert(index);
+        }
+        for (index, group) in registry.enum_groups.iter().enumerate() {
+            registry.group_index.insert(group.name.clone(), index);
+        }
+        for (index, command) in registry.commands.iter().enumerate() {
+            registry.command_index.insert(command.name.clone(), index);
+        }
+        Ok(registry)
+    }
+
+    pub fn type_def(&self, name: &str) -> Option<&TypeDef> {
+        self.type_index.get(name).map(|&index| &self.types[index])
+    }
+
+    pub fn enum_group(&self, name: &str) -> Option<&EnumGroup> {
+        self.group_index.get(name).map(|&index| &self.enum_groups[index])
+    }
+
+    /// Looks a command up, following aliases to the command that has the prototype
+    pub fn command(&self, name: &str) -> Option<&Command> {
+        let command = self.command_index.get(name).map(|&index| &self.commands[index]);
+        match command {
+            Some(&Command { alias: Some(ref alias), .. }) => self.command(alias),
+            other => other
+        }
+    }
+
+    pub fn constant(&self, name: &str) -> Option<&EnumValue> {
+        self.constants.iter().find(|constant| constant.name == name)
+    }
+
+    /// Looks up a core version or an extension by name
+    pub fn interface(&self, name: &str) -> Option<&Interface> {
+        self.features.iter().chain(self.extensions.iter()).find(|interface| interface.name == name)
+    }
+}
@@ -0,0 +1,337 @@
+//! Compares generated modules with the hand-written `src/lib.rs`
+//!
+//! Both sides go through the same scanner, which only understands the formatting dvk uses:
+//! handle macros, `pub const`, `pub type`, enums, `bitflags!`, structs and command tables.
+
+use ::std::collections::BTreeMap;
+use ::std::fmt::Write;
+use ::registry::parse_int;
+
+/// Modules of `src/lib.rs` that have no generated counterpart
+const HAND_WRITTEN_MODULES: &[&str] = &["loader", "window", "device"];
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    Handle,
+    Constant,
+    Type,
+    Enum,
+    Variant,
+    Flags,
+    Flag,
+    Struct,
+    Field,
+    /// The order of a struct's fields
+    Layout,
+    Function,
+    Command
+}
+
+impl Kind {
+    fn label(&self) -> &'static str {
+        match *self {
+            Kind::Handle => "handle",
+            Kind::Constant => "constant",
+            Kind::Type => "type",
+            Kind::Enum => "enum",
+            Kind::Variant => "enum value",
+            Kind::Flags => "flags",
+            Kind::Flag => "flag bit",
+            Kind::Struct => "struct",
+            Kind::Field => "field",
+            Kind::Layout => "field order",
+            Kind::Function => "function type",
+            Kind::Command => "command"
+        }
+    }
+}
+
+/// A definition found by the scanner, keyed by kind and name with its normalized value
+#[derive(Clone, Debug)]
+pub struct Item {
+    pub module: String,
+    pub value: String
+}
+
+pub type Items = BTreeMap<(Kind, String), Item>;
+
+/// Rust primitives that dvk also writes with their libc names
+const PRIMITIVES: &[(&str, &str)] = &[("u8", "uint8_t"), ("u16", "uint16_t"), ("u32", "uint32_t"), ("u64", "uint64_t"),
+                                      ("i8", "int8_t"), ("i16", "int16_t"), ("i32", "int32_t"), ("i64", "int64_t"),
+                                      ("usize", "size_t"), ("f32", "c_float"), ("f64", "c_double")];
+
+/// Collapses whitespace, drops it around punctuation and writes primitives with their libc names
+fn squash(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut out = String::new();
+    for (index, word) in words.iter().enumerate() {
+        let last = out.chars().next_back();
+        let separated = index > 0 && last.map(|c| c.is_alphanumeric() || c == '_').unwrap_or(false)
+            && word.chars().next().map(|c| c.is_alphanumeric() || c == '_').unwrap_or(false);
+        if separated {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    let mut canonical = String::new();
+    let mut token = String::new();
+    for c in out.chars().chain(Some('\0')) {
+        if c.is_alphanumeric() || c == '_' {
+            token.push(c);
+            continue;
+        }
+        let primitive = PRIMITIVES.iter().find(|&&(rust, _)| rust == token);
+        canonical.push_str(primitive.map(|&(_, libc)| libc).unwrap_or(&token));
+        token.clear();
+        if c != '\0' {
+            canonical.push(c);
+        }
+    }
+    canonical
+}
+
+/// Writes integers in decimal so `0x00000010` and `16` compare equal
+fn normalize_number(text: &str) -> String {
+    let text = text.trim().trim_end_matches(',');
+    match parse_int(text) {
+        Some(value) => value.to_string(),
+        None => squash(text)
+    }
+}
+
+/// Reduces a function type to its parameter types and return type, parameter names are ignored
+fn normalize_signature(text: &str) -> String {
+    let open = match text.find("fn(") {
+        Some(open) => open + 3,
+        None => return squash(text)
+    };
+    let mut depth = 0;
+    let mut close = text.len();
+    for (index, c) in text[open..].char_indices() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ']' | '>' => depth -= 1,
+            ')' if depth == 0 => {
+                close = open + index;
+                break;
+            },
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    let params: Vec<String> = text[open..close].split(',')
+        .filter(|param| !param.trim().is_empty())
+        .map(|param| squash(param.split_once(':').map(|(_, ty)| ty).unwrap_or(param)))
+        .collect();
+    let ret = text[close + 1..].trim().trim_end_matches(';').trim_start_matches("->");
+    format!("({}){}", params.join(","), if ret.trim().is_empty() { String::new() } else { format!("->{}", squash(ret)) })
+}
+
+enum Block {
+    None,
+    Enum(String),
+    Flags(String),
+    Struct(String, Vec<String>),
+    Table(String)
+}
+
+/// Collects the definitions of every top-level module in dvk-formatted source
+pub fn scan(source: &str) -> Items {
+    let mut items = Items::new();
+    let mut module = String::new();
+    let mut block = Block::None;
+    let mut pending_type: Option<String> = None;
+    {
+        let mut add = |module: &str, kind: Kind, name: &str, value: String| {
+            items.insert((kind, name.to_string()), Item { module: module.to_string(), value });
+        };
+        for line in source.lines() {
+            if let Some(rest) = line.strip_prefix("pub mod ") {
+                module = rest.trim_end_matches('{').trim().to_string();
+                continue;
+            }
+            let trimmed = line.trim();
+            if let Some(mut text) = pending_type.take() {
+                text.push_str(trimmed);
+                if trimmed.ends_with(';') {
+                    let name = text["pub type ".len()..].split('=').next().unwrap_or("").trim().to_string();
+                    add(&module, Kind::Function, &name, normalize_signature(&text));
+                } else {
+                    text.push(' ');
+                    pending_type = Some(text);
+                }
+                continue;
+            }
+            match block {
+                Block::Enum(ref name) => {
+                    if trimmed.starts_with('}') {
+                        block = Block::None;
+                    } else if let Some((variant, value)) = trimmed.split_once('=') {
+                        add(&module, Kind::Variant, &format!("{}::{}", name, variant.trim()), normalize_number(value));
+                    }
+                    continue;
+                },
+                Block::Flags(ref name) => {
+                    if trimmed.starts_with('}') {
+                        block = Block::None;
+                    } else if let Some(bit) = trimmed.strip_prefix("const ") {
+                        if let Some((bit, value)) = bit.split_once('=') {
+                            add(&module, Kind::Flag, bit.trim(), format!("{} = {}", name, normalize_number(value)));
+                        }
+                    }
+                    continue;
+                },
+                Block::Struct(ref name, ref mut fields) => {
+                    if trimmed.starts_with('}') {
+                        add(&module, Kind::Layout, name, fields.join(","));
+                        block = Block::None;
+                    } else if let Some((field, ty)) = trimmed.trim_start_matches("pub ").split_once(':') {
+                        let field = field.trim().to_string();
+                        add(&module, Kind::Field, &format!("{}.{}", name, field), squash(ty.trim().trim_end_matches(',')));
+                        fields.push(field);
+                    }
+                    continue;
+                },
+                Block::Table(ref name) => {
+                    if trimmed.starts_with('}') {
+                        block = Block::None;
+                    } else if let Some((command, ty)) = trimmed.split_once(':') {
+                        let command = command.trim();
+                        if ty.contains("Command<") && (command != "vkGetInstanceProcAddr" || name == "VkCoreCommands") {
+                            add(&module, Kind::Command, &format!("{}::{}", name, command), String::new());
+                        }
+                    }
+                    continue;
+                },
+                Block::None => {}
+            }
+            if module.is_empty() || HAND_WRITTEN_MODULES.contains(&module.as_str()) {
+                continue;
+            }
+            let handle = trimmed.strip_prefix("VK_DEFINE_HANDLE!(").map(|rest| (rest, "dispatchable"))
+                .or_else(|| trimmed.strip_prefix("VK_DEFINE_NON_DISPATCHABLE_HANDLE!(").map(|rest| (rest, "non-dispatchable")));
+            if let Some((rest, kind)) = handle {
+                add(&module, Kind::Handle, rest.trim_end_matches(");"), kind.to_string());
+            } else if let Some(rest) = trimmed.strip_prefix("pub const ") {
+                if let Some((name, value)) = rest.split_once(':') {
+                    add(&module, Kind::Constant, name.trim(), squash(value.trim_end_matches(';')));
+                }
+            } else if let Some(rest) = trimmed.strip_prefix("pub type ") {
+                if trimmed.contains("fn(") {
+                    if trimmed.ends_with(';') {
+                        let name = rest.split('=').next().unwrap_or("").trim().to_string();
+                        add(&module, Kind::Function, &name, normalize_signature(trimmed));
+                    } else {
+                        pending_type = Some(format!("{} ", trimmed));
+                    }
+                } else if let Some((name, target)) = rest.split_once('=') {
+                    add(&module, Kind::Type, name.trim(), squash(target.trim_end_matches(';')));
+                }
+            } else if let Some(rest) = trimmed.strip_prefix("pub enum ") {
+                let name = rest.trim_end_matches('{').trim();
+                // The `*Union` enums build unions and are not part of the API
+                if !name.ends_with("Union") {
+                    add(&module, Kind::Enum, name, String::new());
+                    block = Block::Enum(name.to_string());
+                } else if !rest.ends_with('}') {
+                    block = Block::Enum(String::new());
+                }
+            } else if let Some(rest) = trimmed.strip_prefix("pub flags ") {
+                if let Some((name, ty)) = rest.split_once(':') {
+                    let name = name.trim().to_string();
+                    add(&module, Kind::Flags, &name, squash(ty.trim_end_matches(['{', ';'])));
+                    if trimmed.ends_with('{') {
+                        block = Block::Flags(name);
+                    }
+                }
+            } else if let Some(rest) = trimmed.strip_prefix("pub struct ") {
+                if rest.ends_with('{') {
+                    let name = rest.trim_end_matches('{').trim().to_string();
+                    if name.ends_with("Commands") {
+                        block = Block::Table(name);
+                    } else {
+                        add(&module, Kind::Struct, &name, String::new());
+                        block = Block::Struct(name, Vec::new());
+                    }
+                }
+            }
+        }
+    }
+    // Values of the `*Union` helper enums were collected under an empty name
+    items.retain(|(kind, name), _| !(kind == &Kind::Variant && name.starts_with("::")));
+    items
+}
+
+/// The differences between generated and hand-written definitions
+pub struct Report {
+    /// Generated definitions that `src/lib.rs` lacks
+    pub missing: Vec<(Kind, String, Item)>,
+    /// Hand-written definitions the registry doesn't produce
+    pub extra: Vec<(Kind, String, Item)>,
+    /// Definitions whose value differs, with the generated and the hand-written item
+    pub differs: Vec<(Kind, String, Item, Item)>,
+    /// Definitions that are equal but live in another module
+    pub moved: Vec<(Kind, String, Item, Item)>
+}
+
+impl Report {
+    pub fn new(generated: &str, hand_written: &str) -> Report {
+        let generated = scan(generated);
+        let hand_written = scan(hand_written);
+        let mut report = Report { missing: Vec::new(), extra: Vec::new(), differs: Vec::new(), moved: Vec::new() };
+        for (key, item) in &generated {
+            match hand_written.get(key) {
+                None => report.missing.push((key.0.clone(), key.1.clone(), item.clone())),
+                Some(other) if other.value != item.value => report.differs.push((key.0.clone(), key.1.clone(), item.clone(), other.clone())),
+                Some(other) if other.module != item.module => report.moved.push((key.0.clone(), key.1.clone(), item.clone(), other.clone())),
+                Some(_) => {}
+            }
+        }
+        for (key, item) in &hand_written {
+            if !generated.contains_key(key) {
+                report.extra.push((key.0.clone(), key.1.clone(), item.clone()));
+            }
+        }
+        report
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.differs.is_empty() && self.moved.is_empty()
+    }
+
+    /// Whether the generated `name` of this kind is absent from `src/lib.rs`
+    pub fn is_missing(&self, kind: Kind, name: &str) -> bool {
+        self.missing.iter().any(|(missing_kind, missing, _)| *missing_kind == kind && missing == name)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "{} missing from src/lib.rs, {} only in src/lib.rs, {} different, {} in another module",
+                         self.missing.len(), self.extra.len(), self.differs.len(), self.moved.len());
+        if !self.missing.is_empty() {
+            let _ = writeln!(text, "\n## Missing from src/lib.rs\n");
+            for (kind, name, item) in &self.missing {
+                let _ = writeln!(text, "{}::{} {} {}", item.module, name, kind.label(), item.value);
+            }
+        }
+        if !self.extra.is_empty() {
+            let _ = writeln!(text, "\n## Only in src/lib.rs\n");
+            for (kind, name, item) in &self.extra {
+                let _ = writeln!(text, "{}::{} {} {}", item.module, name, kind.label(), item.value);
+            }
+        }
+        if !self.differs.is_empty() {
+            let _ = writeln!(text, "\n## Different\n");
+            for (kind, name, generated, hand_written) in &self.differs {
+                let _ = writeln!(text, "{}::{} {}\n    generated:    {}\n    src/lib.rs:   {}", hand_written.module, name, kind.label(), generated.value, hand_written.value);
+            }
+        }
+        if !self.moved.is_empty() {
+            let _ = writeln!(text, "\n## In another module\n");
+            for (kind, name, generated, hand_written) in &self.moved {
+                let _ = writeln!(text, "{} {} generated in {}, hand-written in {}", name, kind.label(), generated.module, hand_written.module);
+            }
+        }
+        text
+    }
+}
@@ -0,0 +1,165 @@
+extern crate dvk_generator;
+
+use dvk_generator::{GeneratorError, REGISTRY_PATH};
+use dvk_generator::emit::{generate, render, table_name, module_name, HAND_WRITTEN_EXTENSIONS};
+use dvk_generator::registry::{Registry, Value};
+use dvk_generator::report::{Kind, Report};
+use std::fs::File;
+use std::io::Read;
+
+const SUBSET: &str = include_str!("fixtures/vk_subset.xml");
+const LIB: &str = include_str!("../../src/lib.rs");
+
+fn generated(extensions: &[&str]) -> String {
+    let registry = Registry::parse(SUBSET).unwrap();
+    render(&generate(&registry, extensions).unwrap())
+}
+
+#[test]
+fn parse_registry() {
+    let registry = Registry::parse(SUBSET).unwrap();
+    assert_eq!(registry.features.iter().map(|feature| feature.name.as_str()).collect::<Vec<_>>(), vec!["VK_VERSION_1_0", "VK_VERSION_1_1"]);
+    assert_eq!(registry.extensions.len(), 2);
+    assert!(registry.type_def("VkSafetyCriticalInfo").is_none());
+    let surface = registry.interface("VK_KHR_surface").unwrap();
+    let lost = surface.requires[0].enums.iter().find(|value| value.name == "VK_ERROR_SURFACE_LOST_KHR").unwrap();
+    assert_eq!(lost.value, Some(Value::Int(-1000000000)));
+    let pool = registry.interface("VK_VERSION_1_1").unwrap().requires[0].enums[0].clone();
+    assert_eq!(pool.value, Some(Value::Int(-1000069000)));
+    let proc_addr = registry.command("vkGetInstanceProcAddr").unwrap();
+    assert_eq!(proc_addr.ret.name, "PFN_vkVoidFunction");
+    assert_eq!(registry.command("vkDestroySurfaceAliasKHR").unwrap().name, "vkDestroySurfaceKHR");
+    match Registry::parse("<types/>") {
+        Err(GeneratorError::Xml(_)) => {},
+        _ => panic!("parsed a document that is not a registry")
+    }
+}
+
+#[test]
+fn names() {
+    assert_eq!(module_name("VK_VERSION_1_0"), "core");
+    assert_eq!(module_name("VK_VERSION_1_2"), "core_1_2");
+    assert_eq!(module_name("VK_KHR_display_swapchain"), "khr_display_swapchain");
+    assert_eq!(table_name("VK_VERSION_1_0"), "VkCoreCommands");
+    assert_eq!(table_name("VK_VERSION_1_3"), "VkCore13Commands");
+    assert_eq!(table_name("VK_EXT_debug_report"), "VkExtDebugReportCommands");
+}
+
+#[test]
+fn dvk_style() {
+    let text = generated(&["VK_KHR_surface", "VK_KHR_surface_alias"]);
+    assert!(text.contains("pub mod core {\n"));
+    assert!(text.contains("pub mod core_1_1 {\n"));
+    assert!(text.contains("    VK_DEFINE_HANDLE!(VkInstance);\n"));
+    assert!(text.contains("    VK_DEFINE_NON_DISPATCHABLE_HANDLE!(VkSurfaceKHR);\n"));
+    assert!(text.contains("    pub const VK_UUID_SIZE:size_t = 16usize;\n"));
+    assert!(text.contains("    pub const VK_QUEUE_FAMILY_EXTERNAL:uint32_t = !0x0u32 - 1;\n"));
+    assert!(text.contains("    pub const VK_WHOLE_SIZE:uint64_t = !0x0u64;\n"));
+    assert!(text.contains("    pub const VK_LOD_CLAMP_NONE:c_float = 1000.0f32;\n"));
+    assert!(text.contains("        VK_ERROR_FRAGMENTED_POOL = -12,\n        VK_ERROR_UNKNOWN = -13,\n        VK_ERROR_OUT_OF_POOL_MEMORY = -1000069000"));
+    assert!(text.contains("            const VK_QUEUE_PROTECTED_BIT = 0x00000010\n"));
+    assert!(text.contains("            const VK_PIPELINE_STAGE_2_COPY_BIT = 0x0000000100000000\n"));
+    assert!(text.contains("        pub flags VkInstanceCreateFlags: VkFlags;\n"));
+    assert!(text.contains("        pub ppEnabledLayerNames: *const *const c_char\n"));
+    assert!(text.contains("        pub deviceName: [c_char;VK_MAX_PHYSICAL_DEVICE_NAME_SIZE],\n"));
+    assert!(text.contains("        pub matrix: [[c_float;4];3]\n"));
+    assert!(text.contains("        pub instanceCustomIndexAndMask: uint32_t\n"));
+    assert!(text.contains("        union_data: [u8;16]\n"));
+    assert!(text.contains("        pub pfnAllocation: Option<vkAllocationFunctionFn>\n"));
+    assert!(text.contains("        pub currentTransform: VkSurfaceTransformFlagsKHR\n"));
+    assert!(text.contains("allocationScope: VkSystemAllocationScope) -> *mut c_void;\n"));
+    assert!(text.contains("pName: *const c_char) -> vkVoidFunctionFn;\n"));
+    assert!(text.contains("    pub const VK_KHR_SURFACE_EXTENSION_NAME: *const c_char = b\"VK_KHR_surface\\0\" as *const u8 as *const c_char;\n"));
+    assert!(text.contains("    pub const VK_COLORSPACE_SRGB_NONLINEAR_KHR: VkColorSpaceKHR = VkColorSpaceKHR::VK_COLOR_SPACE_SRGB_NONLINEAR_KHR;\n"));
+    assert!(text.contains("    pub const VK_STRUCTURE_TYPE_APPLICATION_INFO_KHR: VkStructureType = VkStructureType::VK_STRUCTURE_TYPE_APPLICATION_INFO;\n"));
+    assert!(text.contains("    pub type VkPipelineStageFlags2KHR = VkPipelineStageFlags2;\n"));
+    assert!(text.contains("    pub struct VkKhrSurfaceCommands {\n"));
+    assert!(text.contains("            self.reset_commands();\n            if api_version < VK_API_VERSION_1_1 {"));
+    assert!(text.contains("        fn reset_commands(&self) {\n            let null = ::std::ptr::null();\n"));
+    assert!(text.contains("                vulkan_core_1_1.vkEnumerateInstanceVersion.set(entry.vkGetInstanceProcAddr(VkInstance::null(), b\"vkEnumerateInstanceVersion\\0\".as_ptr() as *const c_char));\n"));
+    assert!(text.contains("                vulkan_core.vkCreateInstance.set(load_command!(vulkan_core, \"vkCreateInstance\", report));\n"));
+    assert!(text.contains("        pub unsafe fn vkDestroySurfaceKHR(&self, \n                                          instance: VkInstance,\n"));
+    assert!(!text.contains("VkSafetyCriticalInfo"));
+    assert!(!text.contains("VK_KHR_DISABLED"));
+}
+
+#[test]
+fn unknown_extension() {
+    let registry = Registry::parse(SUBSET).unwrap();
+    match generate(&registry, &["VK_KHR_missing"]) {
+        Err(GeneratorError::UnknownInterface(name)) => assert_eq!(name, "VK_KHR_missing"),
+        _ => panic!("generated an extension that is not in the registry")
+    }
+}
+
+#[test]
+fn report_against_lib() {
+    let report = Report::new(&generated(&["VK_KHR_surface"]), LIB);
+    assert!(report.is_missing(Kind::Struct, "VkTransformMatrixKHR"));
+    assert!(!report.is_missing(Kind::Variant, "VkResult::VK_ERROR_FRAGMENTED_POOL"));
+    assert!(!report.is_missing(Kind::Variant, "VkResult::VK_ERROR_UNKNOWN"));
+    assert!(!report.is_missing(Kind::Variant, "VkResult::VK_ERROR_OUT_OF_POOL_MEMORY"));
+    assert!(!report.is_missing(Kind::Command, "VkKhrSurfaceCommands::vkDestroySurfaceKHR"));
+    assert!(report.differs.iter().any(|(kind, name, _, _)| *kind == Kind::Function && name == "vkAllocationFunctionFn"));
+    let text = report.to_text();
+    assert!(text.contains("## Missing from src/lib.rs"));
+    assert!(text.contains("core::VkTransformMatrixKHR struct"));
+}
+
+#[test]
+fn report_of_identical_sources() {
+    let text = generated(&["VK_KHR_surface"]);
+    let report = Report::new(&text, &text);
+    assert!(report.is_empty());
+}
+
+// Definitions of src/lib.rs that disagree with the vendored registry: documented deviations,
+// platform types from the `platform` module, older extension spec versions and known binding bugs
+const KNOWN_DIFFERENCES: &[&str] = &["core::VkAllocationCallbacks.pUserData",
+                                     "core::VkPhysicalDeviceLimits",
+                                     "core::vkAllocationFunctionFn",
+                                     "core::vkCmdSetBlendConstantsFn",
+                                     "core::vkCmdUpdateBufferFn",
+                                     "core::vkGetQueryPoolResultsFn",
+                                     "core::vkReallocationFunctionFn",
+                                     "ext_debug_report::VK_EXT_DEBUG_REPORT_SPEC_VERSION",
+                                     "ext_debug_utils::VkDebugUtilsObjectNameInfoEXT.objectType",
+                                     "ext_debug_utils::VkDebugUtilsObjectTagInfoEXT.objectType",
+                                     "khr_display::VK_KHR_DISPLAY_SPEC_VERSION",
+                                     "khr_display_swapchain::VK_KHR_DISPLAY_SWAPCHAIN_SPEC_VERSION",
+                                     "khr_external_memory_capabilities::VK_LUID_SIZE_KHR",
+                                     "khr_swapchain::VK_KHR_SWAPCHAIN_SPEC_VERSION",
+                                     "khr_wayland_surface::VkWaylandSurfaceCreateInfoKHR.display",
+                                     "khr_wayland_surface::VkWaylandSurfaceCreateInfoKHR.surface",
+                                     "khr_wayland_surface::vkGetPhysicalDeviceWaylandPresentationSupportKHRFn",
+                                     "khr_win32_surface::VK_KHR_WIN32_SURFACE_SPEC_VERSION",
+                                     "khr_win32_surface::VkWin32SurfaceCreateInfoKHR.hinstance",
+                                     "khr_win32_surface::VkWin32SurfaceCreateInfoKHR.hwnd",
+                                     "khr_xcb_surface::VkXcbSurfaceCreateInfoKHR.connection",
+                                     "khr_xcb_surface::VkXcbSurfaceCreateInfoKHR.window",
+                                     "khr_xcb_surface::vkGetPhysicalDeviceXcbPresentationSupportKHRFn",
+                                     "khr_xlib_surface::VkXlibSurfaceCreateInfoKHR.dpy",
+                                     "khr_xlib_surface::VkXlibSurfaceCreateInfoKHR.window",
+                                     "khr_xlib_surface::vkGetPhysicalDeviceXlibPresentationSupportKHRFn"];
+
+#[test]
+fn full_registry() {
+    let mut xml = String::new();
+    File::open(REGISTRY_PATH).unwrap().read_to_string(&mut xml).unwrap();
+    let registry = Registry::parse(&xml).unwrap();
+    for feature in &["VK_VERSION_1_0", "VK_VERSION_1_1", "VK_VERSION_1_2", "VK_VERSION_1_3"] {
+        assert!(registry.interface(feature).is_some(), "{} is not in the registry", feature);
+    }
+    let generated = render(&generate(&registry, HAND_WRITTEN_EXTENSIONS).unwrap());
+    for extension in HAND_WRITTEN_EXTENSIONS {
+        assert!(generated.contains(&format!("pub mod {} {{", module_name(extension))), "{} was not generated", extension);
+    }
+    let report = Report::new(&generated, LIB);
+    let missing_core: Vec<&str> = report.missing.iter().filter(|(kind, name, _)| *kind == Kind::Command && name.starts_with("VkCore")).map(|(_, name, _)| name.as_str()).collect();
+    assert!(missing_core.is_empty(), "core commands missing from src/lib.rs: {:?}", missing_core);
+    assert!(!report.is_missing(Kind::Variant, "VkResult::VK_ERROR_UNKNOWN"));
+    assert!(!report.is_missing(Kind::Variant, "VkColorSpaceKHR::VK_COLOR_SPACE_SRGB_NONLINEAR_KHR"));
+    let mut differences: Vec<String> = report.differs.iter().map(|(_, name, generated, _)| format!("{}::{}", generated.module, name)).collect();
+    differences.sort();
+    assert_eq!(differences, KNOWN_DIFFERENCES);
+}
@@ -0,0 +1,327 @@
+#![cfg(all(unix, not(target_os = "macos")))]
+#![allow(non_snake_case)]
+
+extern crate dvk;
+extern crate libc;
+
+mod common;
+
+use dvk::core::*;
+use dvk::ext_debug_report::*;
+use dvk::loader::*;
+use libc::{c_char, c_void, size_t};
+use std::ffi::CStr;
+use std::mem::transmute;
+use std::ptr::{self, null};
+
+/// Driver that records the arguments of the commands it receives in `stub_record`, so they can be
+/// compared with what was passed on the Rust side
+const ABI_STUB: &str = r#"
+#include <stddef.h>
+#include <stdint.h>
+#include <string.h>
+
+typedef void (*PFN_vkVoidFunction)(void);
+typedef uint32_t (*PFN_vkDebugReportCallbackEXT)(uint32_t, uint32_t, uint64_t, size_t, int32_t,
+                                                 const char *, const char *, void *);
+
+typedef struct {
+    uint32_t sType;
+    const void *pNext;
+    uint32_t flags;
+    PFN_vkDebugReportCallbackEXT pfnCallback;
+    void *pUserData;
+} VkDebugReportCallbackCreateInfoEXT;
+
+struct record {
+    uint64_t command_buffer;
+    float floats[4];
+    uint32_t uints[4];
+    int32_t vertex_offset;
+    uint64_t handles[2];
+    uint64_t sizes[2];
+    uint32_t flags;
+    uint32_t callback_result;
+};
+
+struct record stub_record;
+
+static PFN_vkDebugReportCallbackEXT callback;
+static void *callback_user_data;
+static uint32_t callback_flags;
+
+int vkCreateInstance(const void *pCreateInfo, const void *pAllocator, void **pInstance) {
+    *pInstance = (void *)0x1;
+    return 0;
+}
+
+int vkEnumerateInstanceExtensionProperties(const char *pLayerName, uint32_t *pCount, void *pProperties) {
+    *pCount = 0;
+    return 0;
+}
+
+int vkEnumerateInstanceLayerProperties(uint32_t *pCount, void *pProperties) {
+    *pCount = 0;
+    return 0;
+}
+
+void vkCmdSetDepthBias(void *commandBuffer, float constantFactor, float clamp, float slopeFactor) {
+    stub_record.command_buffer = (uintptr_t)commandBuffer;
+    stub_record.floats[0] = constantFactor;
+    stub_record.floats[1] = clamp;
+    stub_record.floats[2] = slopeFactor;
+}
+
+void vkCmdSetBlendConstants(void *commandBuffer, const float blendConstants[4]) {
+    stub_record.command_buffer = (uintptr_t)commandBuffer;
+    memcpy(stub_record.floats, blendConstants, sizeof(stub_record.floats));
+}
+
+void vkCmdDrawIndexed(void *commandBuffer, uint32_t indexCount, uint32_t instanceCount,
+                      uint32_t firstIndex, int32_t vertexOffset, uint32_t firstInstance) {
+    stub_record.command_buffer = (uintptr_t)commandBuffer;
+    stub_record.uints[0] = indexCount;
+    stub_record.uints[1] = instanceCount;
+    stub_record.uints[2] = firstIndex;
+    stub_record.uints[3] = firstInstance;
+    stub_record.vertex_offset = vertexOffset;
+}
+
+void vkCmdCopyQueryPoolResults(void *commandBuffer, uint64_t queryPool, uint32_t firstQuery,
+                               uint32_t queryCount, uint64_t dstBuffer, uint64_t dstOffset,
+                               uint64_t stride, uint32_t flags) {
+    stub_record.command_buffer = (uintptr_t)commandBuffer;
+    stub_record.handles[0] = queryPool;
+    stub_record.handles[1] = dstBuffer;
+    stub_record.uints[0] = firstQuery;
+    stub_record.uints[1] = queryCount;
+    stub_record.sizes[0] = dstOffset;
+    stub_record.sizes[1] = stride;
+    stub_record.flags = flags;
+}
+
+void vkCmdPushConstants(void *commandBuffer, uint64_t layout, uint32_t stageFlags,
+                        uint32_t offset, uint32_t size, const void *pValues) {
+    stub_record.command_buffer = (uintptr_t)commandBuffer;
+    stub_record.handles[0] = layout;
+    stub_record.flags = stageFlags;
+    stub_record.uints[0] = offset;
+    stub_record.uints[1] = size;
+    memcpy(stub_record.floats, pValues, size);
+}
+
+int vkCreateDebugReportCallbackEXT(void *instance, const VkDebugReportCallbackCreateInfoEXT *pCreateInfo,
+                                   const void *pAllocator, uint64_t *pCallback) {
+    callback = pCreateInfo->pfnCallback;
+    callback_user_data = pCreateInfo->pUserData;
+    callback_flags = pCreateInfo->flags;
+    *pCallback = 0xdeb;
+    return 0;
+}
+
+void vkDestroyDebugReportCallbackEXT(void *instance, uint64_t callback_handle, const void *pAllocator) {
+    callback = 0;
+}
+
+void vkDebugReportMessageEXT(void *instance, uint32_t flags, uint32_t objectType, uint64_t object,
+                             size_t location, int32_t messageCode, const char *pLayerPrefix,
+                             const char *pMessage) {
+    if (callback && (flags & callback_flags)) {
+        stub_record.callback_result = callback(flags, objectType, object, location, messageCode,
+                                               pLayerPrefix, pMessage, callback_user_data);
+    }
+}
+
+#define ENTRY(name) if (strcmp(pName, #name) == 0) return (PFN_vkVoidFunction)name;
+
+PFN_vkVoidFunction vkGetInstanceProcAddr(void *instance, const char *pName) {
+    ENTRY(vkGetInstanceProcAddr)
+    ENTRY(vkCreateInstance)
+    ENTRY(vkEnumerateInstanceExtensionProperties)
+    ENTRY(vkEnumerateInstanceLayerProperties)
+    if (!instance) {
+        return 0;
+    }
+    ENTRY(vkCmdSetDepthBias)
+    ENTRY(vkCmdSetBlendConstants)
+    ENTRY(vkCmdDrawIndexed)
+    ENTRY(vkCmdCopyQueryPoolResults)
+    ENTRY(vkCmdPushConstants)
+    ENTRY(vkCreateDebugReportCallbackEXT)
+    ENTRY(vkDestroyDebugReportCallbackEXT)
+    ENTRY(vkDebugReportMessageEXT)
+    return 0;
+}
+"#;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Record {
+    command_buffer: u64,
+    floats: [f32; 4],
+    uints: [u32; 4],
+    vertex_offset: i32,
+    handles: [u64; 2],
+    sizes: [u64; 2],
+    flags: u32,
+    callback_result: u32,
+}
+
+/// Loads the stub and returns the entry, the instance level tables and the stub's record
+fn load_stub(name: &str) -> (Entry, VkCoreCommands, VkInstance, *mut Record) {
+    let stub = common::build_stub(name, ABI_STUB);
+    let entry = Entry::with_config(&LoaderConfig::new().library_path(&stub)).unwrap();
+    let mut core = VkCoreCommands::from_entry(&entry).unwrap();
+    let mut instance = VkInstance::null();
+    unsafe {
+        assert_eq!(core.vkCreateInstance(null(), null(), &mut instance), VkResult::VK_SUCCESS);
+    }
+    core.load_lazy(instance);
+    let record = unsafe { entry.library().unwrap().symbol::<Record>("stub_record").unwrap() };
+    (entry, core, instance, record)
+}
+
+/// Takes a snapshot of what the stub recorded so far
+fn recorded(record: *mut Record) -> Record {
+    unsafe { ptr::read_volatile(record) }
+}
+
+fn command_buffer() -> VkCommandBuffer {
+    unsafe { transmute(0x0123_4567_89ab_cdefusize) }
+}
+
+#[test]
+fn float_arguments() {
+    let (_entry, core, _instance, record) = load_stub("abi_float");
+    unsafe {
+        core.vkCmdSetDepthBias(command_buffer(), 1.5, -0.25, 1e-3);
+    }
+    let depth_bias = recorded(record);
+    assert_eq!(depth_bias.command_buffer, 0x0123_4567_89ab_cdef);
+    assert_eq!(&depth_bias.floats[..3], &[1.5, -0.25, 1e-3]);
+    let blend_constants = [0.125, 0.5, -2.0, 1024.0];
+    unsafe {
+        core.vkCmdSetBlendConstants(command_buffer(), &blend_constants);
+    }
+    assert_eq!(recorded(record).floats, blend_constants);
+}
+
+#[test]
+fn integer_arguments() {
+    let (_entry, core, _instance, record) = load_stub("abi_integer");
+    unsafe {
+        core.vkCmdDrawIndexed(command_buffer(), 36, 2, 0xffff_fff0, -7, 3);
+    }
+    let draw = recorded(record);
+    assert_eq!(draw.uints, [36, 2, 0xffff_fff0, 3]);
+    assert_eq!(draw.vertex_offset, -7);
+    // Eight arguments, more than fit in the argument registers on x86_64
+    let query_pool: VkQueryPool = unsafe { transmute(0x1111_2222_3333_4444u64) };
+    let buffer: VkBuffer = unsafe { transmute(0x5555_6666_7777_8888u64) };
+    unsafe {
+        core.vkCmdCopyQueryPoolResults(command_buffer(), query_pool, 4, 8, buffer,
+                                       0x1_0000_0000, 16, VK_QUERY_RESULT_64_BIT | VK_QUERY_RESULT_WAIT_BIT);
+    }
+    let copy = recorded(record);
+    assert_eq!(copy.command_buffer, 0x0123_4567_89ab_cdef);
+    assert_eq!(copy.handles, [0x1111_2222_3333_4444, 0x5555_6666_7777_8888]);
+    assert_eq!(&copy.uints[..2], &[4, 8]);
+    assert_eq!(copy.sizes, [0x1_0000_0000, 16]);
+    assert_eq!(copy.flags, 0x3);
+}
+
+#[test]
+fn flags_by_value() {
+    let (_entry, core, _instance, record) = load_stub("abi_flags");
+    let layout: VkPipelineLayout = unsafe { transmute(0xabcd_u64) };
+    let values = [3.0f32, 4.0];
+    unsafe {
+        core.vkCmdPushConstants(command_buffer(), layout, VK_SHADER_STAGE_VERTEX_BIT | VK_SHADER_STAGE_FRAGMENT_BIT,
+                                8, 8, values.as_ptr() as *const c_void);
+    }
+    let push = recorded(record);
+    assert_eq!(push.handles[0], 0xabcd);
+    assert_eq!(push.flags, 0x11);
+    assert_eq!(&push.uints[..2], &[8, 8]);
+    assert_eq!(&push.floats[..2], &values);
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Message {
+    flags: u32,
+    object_type: u32,
+    object: u64,
+    location: usize,
+    message_code: i32,
+    layer_prefix: String,
+    message: String,
+}
+
+unsafe extern "system" fn debug_callback(flags: VkDebugReportFlagsEXT,
+                                         objectType: VkDebugReportObjectTypeEXT,
+                                         object: u64,
+                                         location: size_t,
+                                         messageCode: i32,
+                                         pLayerPrefix: *const c_char,
+                                         pMessage: *const c_char,
+                                         pUserData: *mut c_void) -> VkBool32 {
+    let messages = &mut *(pUserData as *mut Vec<Message>);
+    messages.push(Message {
+        flags: flags.bits(),
+        object_type: objectType as u32,
+        object,
+        location,
+        message_code: messageCode,
+        layer_prefix: CStr::from_ptr(pLayerPrefix).to_string_lossy().into_owned(),
+        message: CStr::from_ptr(pMessage).to_string_lossy().into_owned(),
+    });
+    VK_TRUE
+}
+
+#[test]
+fn debug_report_callback() {
+    let (entry, _core, instance, record) = load_stub("abi_callback");
+    let debug_report = VkExtDebugReportCommands::from_entry(&entry, instance).unwrap();
+    let mut messages: Vec<Message> = Vec::new();
+    let create_info = VkDebugReportCallbackCreateInfoEXT {
+        sType: VkStructureType::VK_STRUCTURE_TYPE_DEBUG_REPORT_CALLBACK_CREATE_INFO_EXT,
+        pNext: null(),
+        flags: VK_DEBUG_REPORT_WARNING_BIT_EXT | VK_DEBUG_REPORT_ERROR_BIT_EXT,
+        pfnCallback: Some(debug_callback),
+        pUserData: &mut messages as *mut Vec<Message> as *mut c_void,
+    };
+    let mut callback = VkDebugReportCallbackEXT::null();
+    unsafe {
+        assert_eq!(debug_report.vkCreateDebugReportCallbackEXT(instance, &create_info, null(), &mut callback),
+                   VkResult::VK_SUCCESS);
+        assert!(!callback.is_null());
+        debug_report.vkDebugReportMessageEXT(instance,
+                                             VK_DEBUG_REPORT_WARNING_BIT_EXT,
+                                             VkDebugReportObjectTypeEXT::VK_DEBUG_REPORT_OBJECT_TYPE_BUFFER_EXT,
+                                             0xfeed_f00d_dead_beef,
+                                             usize::MAX,
+                                             -42,
+                                             b"abi\0".as_ptr() as *const c_char,
+                                             b"message\0".as_ptr() as *const c_char);
+        // Filtered out by the callback flags
+        debug_report.vkDebugReportMessageEXT(instance,
+                                             VK_DEBUG_REPORT_INFORMATION_BIT_EXT,
+                                             VkDebugReportObjectTypeEXT::VK_DEBUG_REPORT_OBJECT_TYPE_UNKNOWN_EXT,
+                                             0,
+                                             0,
+                                             0,
+                                             b"abi\0".as_ptr() as *const c_char,
+                                             b"ignored\0".as_ptr() as *const c_char);
+        debug_report.vkDestroyDebugReportCallbackEXT(instance, callback, null());
+    }
+    assert_eq!(recorded(record).callback_result, VK_TRUE);
+    assert_eq!(messages, vec![Message {
+        flags: 0x2,
+        object_type: 9,
+        object: 0xfeed_f00d_dead_beef,
+        location: usize::MAX,
+        message_code: -42,
+        layer_prefix: "abi".to_string(),
+        message: "message".to_string(),
+    }]);
+}
@@ -0,0 +1,89 @@
+#![allow(dead_code)]
+
+use dvk::core::{VkInstance, vkVoidFunctionFn};
+use std::env;
+use std::ffi::CStr;
+use std::fs;
+use std::mem::transmute;
+use std::os::raw::c_char;
+use std::path::PathBuf;
+use std::process::Command;
+use std::ptr::null;
+
+/// Instance handle handed to the fake drivers, they only check that it is not null
+pub fn instance() -> VkInstance {
+    unsafe { transmute(1usize) }
+}
+
+/// Stand-in for commands that have to be loaded but are never called
+pub unsafe extern "system" fn fake_noop() {}
+
+/// `fake_noop` as returned by vkGet*ProcAddr
+pub fn noop() -> vkVoidFunctionFn {
+    fake_noop as unsafe extern "system" fn() as vkVoidFunctionFn
+}
+
+/// Resolves `name` among the commands a test registers, nothing is resolved without an instance
+pub unsafe fn lookup(commands: &[(&str, vkVoidFunctionFn)], instance: VkInstance, name: *const c_char) -> vkVoidFunctionFn {
+    if instance.is_null() {
+        return null();
+    }
+    let name = CStr::from_ptr(name).to_str().unwrap();
+    commands.iter().find(|&&(command, _)| command == name).map(|&(_, fn_ptr)| fn_ptr).unwrap_or(null())
+}
+
+/// Compiles `source` into a shared library in a fresh temporary directory and returns its path
+pub fn build_stub(name: &str, source: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("dvk-{}-{}", name, ::std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let source_path = dir.join(format!("{}.c", name));
+    let library_path = dir.join(format!("lib{}.so", name));
+    fs::write(&source_path, source).unwrap();
+    let compiler = env::var("CC").unwrap_or("cc".to_string());
+    let status = Command::new(compiler)
+        .arg("-shared")
+        .arg("-fPIC")
+        .arg("-o").arg(&library_path)
+        .arg(&source_path)
+        .status()
+        .expect("failed to run C compiler");
+    assert!(status.success(), "failed to compile {}", source_path.display());
+    library_path
+}
+
+/// Minimal driver exporting vkGetInstanceProcAddr, the global commands are implemented and every
+/// other command resolves to a no-op once an instance exists
+pub const DRIVER_STUB: &str = r#"
+#include <stdint.h>
+#include <string.h>
+
+typedef void (*PFN_vkVoidFunction)(void);
+
+static int32_t noop(void) {
+    return 0;
+}
+
+static int32_t vkCreateInstance(const void* pCreateInfo, const void* pAllocator, void** pInstance) {
+    *pInstance = (void*)0x1;
+    return 0;
+}
+
+static int32_t vkEnumerateInstanceExtensionProperties(const char* pLayerName, uint32_t* pCount, void* pProperties) {
+    *pCount = 0;
+    return 0;
+}
+
+static int32_t vkEnumerateInstanceLayerProperties(uint32_t* pCount, void* pProperties) {
+    *pCount = 42;
+    return 0;
+}
+
+PFN_vkVoidFunction vkGetInstanceProcAddr(void* instance, const char* pName) {
+    if (strcmp(pName, "vkGetInstanceProcAddr") == 0) return (PFN_vkVoidFunction)vkGetInstanceProcAddr;
+    if (strcmp(pName, "vkCreateInstance") == 0) return (PFN_vkVoidFunction)vkCreateInstance;
+    if (strcmp(pName, "vkEnumerateInstanceExtensionProperties") == 0) return (PFN_vkVoidFunction)vkEnumerateInstanceExtensionProperties;
+    if (strcmp(pName, "vkEnumerateInstanceLayerProperties") == 0) return (PFN_vkVoidFunction)vkEnumerateInstanceLayerProperties;
+    if (instance) return (PFN_vkVoidFunction)noop;
+    return 0;
+}
+"#;
@@ -0,0 +1,123 @@
+#![allow(non_snake_case)]
+
+extern crate dvk;
+extern crate libc;
+
+mod common;
+
+use dvk::core::*;
+use dvk::ext_debug_utils::*;
+use dvk::loader::*;
+use libc::{c_char, c_void};
+use std::ffi::CStr;
+use std::mem::{size_of, transmute};
+use std::ptr::{null, null_mut};
+use std::sync::Mutex;
+
+static MESSENGER: Mutex<Option<(usize, usize)>> = Mutex::new(None);
+
+unsafe extern "system" fn fake_create_messenger(_instance: VkInstance,
+                                                pCreateInfo: *const VkDebugUtilsMessengerCreateInfoEXT,
+                                                _pAllocator: *const VkAllocationCallbacks,
+                                                pMessenger: *mut VkDebugUtilsMessengerEXT) -> VkResult {
+    let callback = (*pCreateInfo).pfnUserCallback.unwrap();
+    *MESSENGER.lock().unwrap() = Some((callback as usize, (*pCreateInfo).pUserData as usize));
+    *pMessenger = transmute::<u64, VkDebugUtilsMessengerEXT>(0x40);
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_submit_message(_instance: VkInstance,
+                                              messageSeverity: VkDebugUtilsMessageSeverityFlagsEXT,
+                                              messageTypes: VkDebugUtilsMessageTypeFlagsEXT,
+                                              pCallbackData: *const VkDebugUtilsMessengerCallbackDataEXT) {
+    let (callback, user_data) = MESSENGER.lock().unwrap().unwrap();
+    let callback = transmute::<usize, vkDebugUtilsMessengerCallbackEXTFn>(callback);
+    callback(messageSeverity, messageTypes, pCallbackData, user_data as *mut c_void);
+}
+
+unsafe extern "system" fn fake_get_instance_proc_addr(instance: VkInstance, pName: *const c_char) -> vkVoidFunctionFn {
+    common::lookup(&[("vkCreateDebugUtilsMessengerEXT", fake_create_messenger as vkCreateDebugUtilsMessengerEXTFn as vkVoidFunctionFn),
+                     ("vkSubmitDebugUtilsMessageEXT", fake_submit_message as vkSubmitDebugUtilsMessageEXTFn as vkVoidFunctionFn)],
+                   instance, pName)
+}
+
+unsafe extern "system" fn record_message(messageSeverity: VkDebugUtilsMessageSeverityFlagsEXT,
+                                         messageTypes: VkDebugUtilsMessageTypeFlagsEXT,
+                                         pCallbackData: *const VkDebugUtilsMessengerCallbackDataEXT,
+                                         pUserData: *mut c_void) -> VkBool32 {
+    let messages = &mut *(pUserData as *mut Vec<String>);
+    let label = &*(*pCallbackData).pQueueLabels;
+    messages.push(format!("{:?} {:?} {} {} {:?}",
+                          messageSeverity,
+                          messageTypes,
+                          CStr::from_ptr((*pCallbackData).pMessage).to_str().unwrap(),
+                          CStr::from_ptr(label.pLabelName).to_str().unwrap(),
+                          label.color));
+    VK_FALSE
+}
+
+#[test]
+fn messenger_callback() {
+    let instance = common::instance();
+    let (debug_utils, report) = unsafe { VkExtDebugUtilsCommands::from_entry_lenient(&Entry::from_proc_addr(fake_get_instance_proc_addr), instance) };
+    assert_eq!(report.missing.len(), 9);
+    assert!(report.missing.contains(&"vkSetDebugUtilsObjectNameEXT"));
+    let mut messages: Vec<String> = Vec::new();
+    let create_info = VkDebugUtilsMessengerCreateInfoEXT {
+        sType: VkStructureType::VK_STRUCTURE_TYPE_DEBUG_UTILS_MESSENGER_CREATE_INFO_EXT,
+        pNext: null(),
+        flags: VkDebugUtilsMessengerCreateFlagsEXT::empty(),
+        messageSeverity: VK_DEBUG_UTILS_MESSAGE_SEVERITY_WARNING_BIT_EXT | VK_DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT,
+        messageType: VK_DEBUG_UTILS_MESSAGE_TYPE_VALIDATION_BIT_EXT,
+        pfnUserCallback: Some(record_message),
+        pUserData: &mut messages as *mut Vec<String> as *mut c_void
+    };
+    let label = VkDebugUtilsLabelEXT {
+        sType: VkStructureType::VK_STRUCTURE_TYPE_DEBUG_UTILS_LABEL_EXT,
+        pNext: null(),
+        pLabelName: b"frame\0".as_ptr() as *const c_char,
+        color: [1.0, 0.5, 0.0, 1.0]
+    };
+    let callback_data = VkDebugUtilsMessengerCallbackDataEXT {
+        sType: VkStructureType::VK_STRUCTURE_TYPE_DEBUG_UTILS_MESSENGER_CALLBACK_DATA_EXT,
+        pNext: null(),
+        flags: VkDebugUtilsMessengerCallbackDataFlagsEXT::empty(),
+        pMessageIdName: null(),
+        messageIdNumber: 0,
+        pMessage: b"hello\0".as_ptr() as *const c_char,
+        queueLabelCount: 1,
+        pQueueLabels: &label,
+        cmdBufLabelCount: 0,
+        pCmdBufLabels: null(),
+        objectCount: 0,
+        pObjects: null()
+    };
+    let mut messenger = VkDebugUtilsMessengerEXT::null();
+    unsafe {
+        assert_eq!(debug_utils.vkCreateDebugUtilsMessengerEXT(instance, &create_info, null(), &mut messenger), VkResult::VK_SUCCESS);
+        debug_utils.vkSubmitDebugUtilsMessageEXT(instance, VK_DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT, VK_DEBUG_UTILS_MESSAGE_TYPE_VALIDATION_BIT_EXT, &callback_data);
+        assert!(debug_utils.try_vkDestroyDebugUtilsMessengerEXT(instance, messenger, null_mut()).is_err());
+    }
+    assert!(!messenger.is_null());
+    assert_eq!(messages, vec!["VK_DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT VK_DEBUG_UTILS_MESSAGE_TYPE_VALIDATION_BIT_EXT hello frame [1.0, 0.5, 0.0, 1.0]"]);
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn struct_layout() {
+    // Sizes from the C headers on 64-bit targets
+    assert_eq!(size_of::<VkDebugUtilsLabelEXT>(), 40);
+    assert_eq!(size_of::<VkDebugUtilsObjectNameInfoEXT>(), 40);
+    assert_eq!(size_of::<VkDebugUtilsObjectTagInfoEXT>(), 56);
+    assert_eq!(size_of::<VkDebugUtilsMessengerCallbackDataEXT>(), 96);
+    assert_eq!(size_of::<VkDebugUtilsMessengerCreateInfoEXT>(), 48);
+}
+
+#[test]
+fn object_types() {
+    assert_eq!(VkObjectType::VK_OBJECT_TYPE_DEBUG_UTILS_MESSENGER_EXT as u32, 1000128000);
+    assert_eq!(RawObjectType::from(VkObjectType::VK_OBJECT_TYPE_DEBUG_UTILS_MESSENGER_EXT), RawObjectType::VK_OBJECT_TYPE_DEBUG_UTILS_MESSENGER_EXT);
+    assert_eq!(RawObjectType::VK_OBJECT_TYPE_SEMAPHORE.object_type(), Some(VkObjectType::VK_OBJECT_TYPE_SEMAPHORE));
+    // An object type added after this library, reported by a newer driver or layer
+    assert_eq!(RawObjectType(1000150000).object_type(), None);
+}
@@ -0,0 +1,131 @@
+#![allow(non_snake_case)]
+
+extern crate dvk;
+extern crate libc;
+
+mod common;
+
+use dvk::core::*;
+use dvk::core_1_1::*;
+use dvk::core_1_3::*;
+use dvk::device::*;
+use dvk::loader::*;
+use libc::c_char;
+use std::ffi::CStr;
+use std::mem::transmute;
+use std::ptr::null;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// The fake driver behaves differently per device handle so tests can run in parallel
+const FULL_DEVICE: usize = 1;
+const CORE_ONLY_DEVICE: usize = 2;
+const BROKEN_DEVICE: usize = 3;
+const COUNTED_DEVICE: usize = 4;
+
+static DRAWN_VERTICES: AtomicU32 = AtomicU32::new(0);
+static COUNTED_LOOKUPS: AtomicU32 = AtomicU32::new(0);
+
+unsafe extern "system" fn fake_cmd_draw(_commandBuffer: VkCommandBuffer, vertexCount: u32, _instanceCount: u32, _firstVertex: u32, _firstInstance: u32) {
+    DRAWN_VERTICES.fetch_add(vertexCount, Ordering::SeqCst);
+}
+
+unsafe extern "system" fn fake_get_device_proc_addr(device: VkDevice, pName: *const c_char) -> vkVoidFunctionFn {
+    let name = CStr::from_ptr(pName).to_str().unwrap();
+    if transmute::<VkDevice, usize>(device) == COUNTED_DEVICE {
+        COUNTED_LOOKUPS.fetch_add(1, Ordering::SeqCst);
+    }
+    match (transmute::<VkDevice, usize>(device), name) {
+        (BROKEN_DEVICE, "vkCmdDraw") => null(),
+        (CORE_ONLY_DEVICE, _) if name.ends_with("KHR") => null(),
+        (_, "vkCmdDraw") => fake_cmd_draw as vkCmdDrawFn as vkVoidFunctionFn,
+        _ => common::noop()
+    }
+}
+
+fn device(handle: usize) -> VkDevice {
+    unsafe { transmute(handle) }
+}
+
+#[test]
+fn strict_load_without_extensions() {
+    let commands = unsafe { VkDeviceCommands::from_proc_addr(fake_get_device_proc_addr, device(CORE_ONLY_DEVICE), VK_API_VERSION_1_0).unwrap() };
+    assert!(commands.is_loaded("vkCmdDraw"));
+    assert!(!commands.is_loaded("vkCreateSwapchainKHR"));
+    assert!(!commands.is_loaded("vkCreateSharedSwapchainsKHR"));
+    assert!(unsafe { commands.try_vkQueuePresentKHR(VkQueue::null(), null()) }.is_err());
+
+    let mut lenient = unsafe { VkDeviceCommands::from_proc_addr(fake_get_device_proc_addr, device(FULL_DEVICE), VK_API_VERSION_1_0).unwrap() };
+    let report = lenient.load_lenient(device(CORE_ONLY_DEVICE), VK_API_VERSION_1_3);
+    assert!(report.is_complete());
+    assert!(lenient.is_loaded("vkCmdBeginRendering"));
+    assert!(!lenient.is_loaded("vkAcquireNextImageKHR"));
+}
+
+#[test]
+fn missing_core_command() {
+    match unsafe { VkDeviceCommands::from_proc_addr(fake_get_device_proc_addr, device(BROKEN_DEVICE), VK_API_VERSION_1_0) } {
+        Err(LoadError::DeviceCommandMissing { name, .. }) => assert_eq!(name, "vkCmdDraw"),
+        _ => panic!("strict load must fail on a missing core command")
+    }
+
+    let mut commands = unsafe { VkDeviceCommands::from_proc_addr(fake_get_device_proc_addr, device(FULL_DEVICE), VK_API_VERSION_1_0).unwrap() };
+    let report = commands.load_lenient(device(BROKEN_DEVICE), VK_API_VERSION_1_1);
+    assert_eq!(report.missing, vec!["vkCmdDraw"]);
+    assert!(commands.is_loaded("vkCmdDispatch"));
+    assert!(commands.is_loaded("vkTrimCommandPool"));
+    assert!(commands.is_loaded("vkCreateSwapchainKHR"));
+}
+
+#[test]
+fn versioned_commands() {
+    let mut commands = unsafe { VkDeviceCommands::from_proc_addr(fake_get_device_proc_addr, device(FULL_DEVICE), VK_API_VERSION_1_0).unwrap() };
+    assert!(commands.is_loaded("vkCreateSwapchainKHR"));
+    assert!(!commands.is_loaded("vkTrimCommandPool"));
+    assert!(!commands.is_loaded("vkCmdBeginRendering"));
+
+    commands.load_lazy(device(FULL_DEVICE), VK_API_VERSION_1_1);
+    assert!(unsafe { commands.try_vkTrimCommandPool(device(FULL_DEVICE), transmute::<u64, VkCommandPool>(0x10), VkCommandPoolTrimFlags::empty()) }.is_ok());
+    assert!(unsafe { commands.try_vkCmdEndRendering(VkCommandBuffer::null()) }.is_err());
+
+    commands.load(device(FULL_DEVICE), VK_API_VERSION_1_3).unwrap();
+    assert!(commands.is_loaded("vkCmdBeginRendering"));
+    assert!(commands.is_loaded("vkGetDeviceImageSparseMemoryRequirements"));
+}
+
+#[test]
+fn lazy_dispatch() {
+    let mut commands = unsafe { VkDeviceCommands::from_proc_addr(fake_get_device_proc_addr, device(FULL_DEVICE), VK_API_VERSION_1_0).unwrap() };
+    commands.load_lazy(device(FULL_DEVICE), VK_API_VERSION_1_0);
+    unsafe { commands.vkCmdDraw(VkCommandBuffer::null(), 3, 1, 0, 0) };
+    assert_eq!(DRAWN_VERTICES.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn reload_unloads_previous_commands() {
+    let mut commands = unsafe { VkDeviceCommands::from_proc_addr(fake_get_device_proc_addr, device(FULL_DEVICE), VK_API_VERSION_1_3).unwrap() };
+    assert!(commands.is_loaded("vkCmdBeginRendering"));
+
+    let report = commands.load_lenient(device(CORE_ONLY_DEVICE), VK_API_VERSION_1_0);
+    assert!(report.is_complete());
+    assert!(!commands.is_loaded("vkTrimCommandPool"));
+    assert!(!commands.is_loaded("vkCmdBeginRendering"));
+    assert!(!commands.is_loaded("vkCreateSwapchainKHR"));
+    assert!(unsafe { commands.try_vkCmdEndRendering(VkCommandBuffer::null()) }.is_err());
+
+    commands.load(device(FULL_DEVICE), VK_API_VERSION_1_3).unwrap();
+    commands.load_lazy(device(FULL_DEVICE), VK_API_VERSION_1_1);
+    assert_eq!(commands.loaded_commands(), vec!["vkGetDeviceProcAddr"]);
+    assert!(unsafe { commands.try_vkCmdEndRendering(VkCommandBuffer::null()) }.is_err());
+}
+
+#[test]
+fn newer_commands_are_not_resolved() {
+    let mut commands = unsafe { VkDeviceCommands::from_proc_addr(fake_get_device_proc_addr, device(FULL_DEVICE), VK_API_VERSION_1_0).unwrap() };
+    commands.load_lazy(device(COUNTED_DEVICE), VK_API_VERSION_1_0);
+    for _ in 0..3 {
+        assert!(unsafe { commands.try_vkCmdEndRendering(VkCommandBuffer::null()) }.is_err());
+    }
+    assert_eq!(COUNTED_LOOKUPS.load(Ordering::SeqCst), 0);
+    unsafe { commands.vkCmdDraw(VkCommandBuffer::null(), 0, 1, 0, 0) };
+    assert_eq!(COUNTED_LOOKUPS.load(Ordering::SeqCst), 1);
+}
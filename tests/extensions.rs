@@ -0,0 +1,352 @@
+#![allow(non_snake_case)]
+
+extern crate dvk;
+extern crate libc;
+
+mod common;
+
+use common::instance;
+use dvk::core::*;
+use dvk::core_1_1::*;
+use dvk::core_1_2::*;
+use dvk::core_1_3::*;
+use dvk::khr_dynamic_rendering::*;
+use dvk::khr_get_physical_device_properties2::*;
+use dvk::khr_external_memory_capabilities::*;
+use dvk::khr_external_memory::*;
+use dvk::khr_external_memory_fd::*;
+use dvk::khr_external_semaphore_fd::*;
+use dvk::khr_external_fence_fd::*;
+use dvk::ext_image_drm_format_modifier::*;
+use dvk::ext_image_drm_format_modifier::drm::*;
+use dvk::khr_display::*;
+use dvk::ext_direct_mode_display::*;
+use dvk::ext_acquire_drm_display::*;
+use dvk::khr_synchronization2::*;
+use dvk::loader::*;
+use libc::{c_char, c_int};
+use std::mem::{transmute, zeroed};
+use std::ptr::{null, null_mut};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+static RENDERING: AtomicU32 = AtomicU32::new(0);
+static STAGES: AtomicU64 = AtomicU64::new(0);
+
+unsafe extern "system" fn fake_get_physical_device_features2(_physicalDevice: VkPhysicalDevice,
+                                                             pFeatures: *mut VkPhysicalDeviceFeatures2) {
+    (*pFeatures).features.samplerAnisotropy = VK_TRUE;
+    let multiview = (*pFeatures).pNext as *mut VkPhysicalDeviceMultiviewFeatures;
+    if !multiview.is_null() && (*multiview).sType == VkStructureType::VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTIVIEW_FEATURES {
+        (*multiview).multiview = VK_TRUE;
+    }
+}
+
+unsafe extern "system" fn fake_get_memory_fd(_device: VkDevice,
+                                             pGetFdInfo: *const VkMemoryGetFdInfoKHR,
+                                             pFd: *mut c_int) -> VkResult {
+    if (*pGetFdInfo).handleType != VK_EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD_BIT_KHR {
+        return VK_ERROR_INVALID_EXTERNAL_HANDLE_KHR;
+    }
+    *pFd = 7;
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_import_semaphore_fd(_device: VkDevice,
+                                                   pImportSemaphoreFdInfo: *const VkImportSemaphoreFdInfoKHR) -> VkResult {
+    match ((*pImportSemaphoreFdInfo).handleType, (*pImportSemaphoreFdInfo).fd) {
+        (VK_EXTERNAL_SEMAPHORE_HANDLE_TYPE_SYNC_FD_BIT, 9) => VkResult::VK_SUCCESS,
+        _ => VkResult::VK_ERROR_INVALID_EXTERNAL_HANDLE
+    }
+}
+
+unsafe extern "system" fn fake_get_image_drm_format_modifier_properties(_device: VkDevice,
+                                                                         _image: VkImage,
+                                                                         pProperties: *mut VkImageDrmFormatModifierPropertiesEXT) -> VkResult {
+    (*pProperties).drmFormatModifier = DRM_FORMAT_MOD_LINEAR;
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_get_drm_display(_physicalDevice: VkPhysicalDevice,
+                                               drmFd: i32,
+                                               connectorId: u32,
+                                               display: *mut VkDisplayKHR) -> VkResult {
+    if drmFd < 0 {
+        return VkResult::VK_ERROR_INITIALIZATION_FAILED;
+    }
+    *display = transmute::<u64, VkDisplayKHR>(0x100 + connectorId as u64);
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_acquire_drm_display(_physicalDevice: VkPhysicalDevice,
+                                                   _drmFd: i32,
+                                                   display: VkDisplayKHR) -> VkResult {
+    if display.is_null() {
+        return VkResult::VK_ERROR_INITIALIZATION_FAILED;
+    }
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_release_display(_physicalDevice: VkPhysicalDevice, _display: VkDisplayKHR) -> VkResult {
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_cmd_begin_rendering(_commandBuffer: VkCommandBuffer, pRenderingInfo: *const VkRenderingInfoKHR) {
+    let info = &*pRenderingInfo;
+    if info.flags.contains(VK_RENDERING_SUSPENDING_BIT_KHR) && (*info.pColorAttachments).storeOp == VK_ATTACHMENT_STORE_OP_NONE_KHR {
+        RENDERING.store(info.colorAttachmentCount, Ordering::SeqCst);
+    }
+}
+
+unsafe extern "system" fn fake_cmd_end_rendering(_commandBuffer: VkCommandBuffer) {
+    RENDERING.store(0, Ordering::SeqCst);
+}
+
+unsafe extern "system" fn fake_cmd_pipeline_barrier2(_commandBuffer: VkCommandBuffer, pDependencyInfo: *const VkDependencyInfoKHR) {
+    let barrier = &*(*pDependencyInfo).pMemoryBarriers;
+    STAGES.fetch_or(barrier.srcStageMask.bits() | barrier.dstStageMask.bits(), Ordering::SeqCst);
+}
+
+unsafe extern "system" fn fake_cmd_write_timestamp2(_commandBuffer: VkCommandBuffer,
+                                                    stage: VkPipelineStageFlags2KHR,
+                                                    _queryPool: VkQueryPool,
+                                                    _query: u32) {
+    STAGES.fetch_or(stage.bits(), Ordering::SeqCst);
+}
+
+unsafe extern "system" fn fake_queue_submit2(_queue: VkQueue,
+                                             submitCount: u32,
+                                             pSubmits: *const VkSubmitInfo2KHR,
+                                             _fence: VkFence) -> VkResult {
+    if submitCount != 1 || (*pSubmits).commandBufferInfoCount != 1 {
+        return VkResult::VK_ERROR_DEVICE_LOST;
+    }
+    let wait = &*(*pSubmits).pWaitSemaphoreInfos;
+    STAGES.fetch_or(wait.stageMask.bits(), Ordering::SeqCst);
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_get_instance_proc_addr(instance: VkInstance, pName: *const c_char) -> vkVoidFunctionFn {
+    common::lookup(&[("vkGetPhysicalDeviceFeatures2KHR", fake_get_physical_device_features2 as vkGetPhysicalDeviceFeatures2KHRFn as vkVoidFunctionFn),
+                     ("vkGetMemoryFdKHR", fake_get_memory_fd as vkGetMemoryFdKHRFn as vkVoidFunctionFn),
+                     ("vkImportSemaphoreFdKHR", fake_import_semaphore_fd as vkImportSemaphoreFdKHRFn as vkVoidFunctionFn),
+                     ("vkGetImageDrmFormatModifierPropertiesEXT", fake_get_image_drm_format_modifier_properties as vkGetImageDrmFormatModifierPropertiesEXTFn as vkVoidFunctionFn),
+                     ("vkGetDrmDisplayEXT", fake_get_drm_display as vkGetDrmDisplayEXTFn as vkVoidFunctionFn),
+                     ("vkAcquireDrmDisplayEXT", fake_acquire_drm_display as vkAcquireDrmDisplayEXTFn as vkVoidFunctionFn),
+                     ("vkReleaseDisplayEXT", fake_release_display as vkReleaseDisplayEXTFn as vkVoidFunctionFn),
+                     ("vkCmdBeginRenderingKHR", fake_cmd_begin_rendering as vkCmdBeginRenderingKHRFn as vkVoidFunctionFn),
+                     ("vkCmdEndRenderingKHR", fake_cmd_end_rendering as vkCmdEndRenderingKHRFn as vkVoidFunctionFn),
+                     ("vkCmdPipelineBarrier2KHR", fake_cmd_pipeline_barrier2 as vkCmdPipelineBarrier2KHRFn as vkVoidFunctionFn),
+                     ("vkCmdWriteTimestamp2KHR", fake_cmd_write_timestamp2 as vkCmdWriteTimestamp2KHRFn as vkVoidFunctionFn),
+                     ("vkQueueSubmit2KHR", fake_queue_submit2 as vkQueueSubmit2KHRFn as vkVoidFunctionFn)],
+                   instance, pName)
+}
+
+#[test]
+fn physical_device_properties2() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let (properties2, report) = VkKhrGetPhysicalDeviceProperties2Commands::from_entry_lenient(&entry, instance());
+    assert_eq!(report.missing.len(), 6);
+    assert!(properties2.is_loaded("vkGetPhysicalDeviceFeatures2KHR"));
+    let mut multiview: VkPhysicalDeviceMultiviewFeatures = unsafe { zeroed() };
+    multiview.sType = VkStructureType::VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTIVIEW_FEATURES;
+    let mut features: VkPhysicalDeviceFeatures2KHR = unsafe { zeroed() };
+    features.sType = VK_STRUCTURE_TYPE_PHYSICAL_DEVICE_FEATURES_2_KHR;
+    features.pNext = &mut multiview as *mut VkPhysicalDeviceMultiviewFeatures as *mut _;
+    unsafe {
+        properties2.vkGetPhysicalDeviceFeatures2KHR(VkPhysicalDevice::null(), &mut features);
+    }
+    assert_eq!(features.features.samplerAnisotropy, VK_TRUE);
+    assert_eq!(multiview.multiview, VK_TRUE);
+}
+
+#[test]
+fn external_fd() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let memory_fd = VkKhrExternalMemoryFdCommands::from_entry_lazy(&entry, instance());
+    let mut get_fd_info = VkMemoryGetFdInfoKHR {
+        sType: VkStructureType::VK_STRUCTURE_TYPE_MEMORY_GET_FD_INFO_KHR,
+        pNext: null(),
+        memory: VkDeviceMemory::null(),
+        handleType: VK_EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD_BIT_KHR
+    };
+    let mut fd: c_int = -1;
+    unsafe {
+        assert_eq!(memory_fd.vkGetMemoryFdKHR(VkDevice::null(), &get_fd_info, &mut fd), VkResult::VK_SUCCESS);
+        assert_eq!(fd, 7);
+        get_fd_info.handleType = VK_EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_HEAP_BIT;
+        assert_eq!(memory_fd.vkGetMemoryFdKHR(VkDevice::null(), &get_fd_info, &mut fd), VkResult::VK_ERROR_INVALID_EXTERNAL_HANDLE);
+        assert!(memory_fd.try_vkGetMemoryFdPropertiesKHR(VkDevice::null(), VK_EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD_BIT, fd, &mut zeroed()).is_err());
+    }
+
+    let semaphore_fd = VkKhrExternalSemaphoreFdCommands::from_entry_lazy(&entry, instance());
+    let import_info = VkImportSemaphoreFdInfoKHR {
+        sType: VkStructureType::VK_STRUCTURE_TYPE_IMPORT_SEMAPHORE_FD_INFO_KHR,
+        pNext: null(),
+        semaphore: VkSemaphore::null(),
+        flags: VK_SEMAPHORE_IMPORT_TEMPORARY_BIT,
+        handleType: VK_EXTERNAL_SEMAPHORE_HANDLE_TYPE_SYNC_FD_BIT,
+        fd: 9
+    };
+    unsafe {
+        assert_eq!(semaphore_fd.vkImportSemaphoreFdKHR(VkDevice::null(), &import_info), VkResult::VK_SUCCESS);
+    }
+
+    let (_, report) = VkKhrExternalFenceFdCommands::from_entry_lenient(&entry, instance());
+    assert_eq!(report.missing, vec!["vkImportFenceFdKHR", "vkGetFenceFdKHR"]);
+}
+
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn external_fd_struct_sizes() {
+    assert_eq!(std::mem::size_of::<VkImportMemoryFdInfoKHR>(), 24);
+    assert_eq!(std::mem::size_of::<VkMemoryFdPropertiesKHR>(), 24);
+    assert_eq!(std::mem::size_of::<VkMemoryGetFdInfoKHR>(), 32);
+    assert_eq!(std::mem::size_of::<VkImportSemaphoreFdInfoKHR>(), 40);
+    assert_eq!(std::mem::size_of::<VkImportFenceFdInfoKHR>(), 40);
+    assert_eq!(std::mem::size_of::<VkFenceGetFdInfoKHR>(), 32);
+}
+
+#[test]
+fn drm_formats() {
+    assert_eq!(DRM_FORMAT_XRGB8888, 0x34325258);
+    assert_eq!(DRM_FORMAT_NV12, 0x3231564e);
+    assert_eq!(format_from_fourcc(DRM_FORMAT_ARGB8888), Some(VkFormat::VK_FORMAT_B8G8R8A8_UNORM));
+    assert_eq!(format_from_fourcc(DRM_FORMAT_XBGR8888), Some(VkFormat::VK_FORMAT_R8G8B8A8_UNORM));
+    assert_eq!(format_from_fourcc(DRM_FORMAT_NV12), Some(VkFormat::VK_FORMAT_G8_B8R8_2PLANE_420_UNORM));
+    assert_eq!(format_from_fourcc(DRM_FORMAT_P010), Some(VkFormat::VK_FORMAT_G10X6_B10X6R10X6_2PLANE_420_UNORM_3PACK16));
+    assert_eq!(format_from_fourcc(fourcc_code(b'C', b'8', b' ', b' ')), None);
+
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let drm_format_modifier = VkExtImageDrmFormatModifierCommands::from_entry(&entry, instance()).unwrap();
+    let mut properties = VkImageDrmFormatModifierPropertiesEXT {
+        sType: VkStructureType::VK_STRUCTURE_TYPE_IMAGE_DRM_FORMAT_MODIFIER_PROPERTIES_EXT,
+        pNext: null_mut(),
+        drmFormatModifier: DRM_FORMAT_MOD_INVALID
+    };
+    unsafe {
+        assert_eq!(drm_format_modifier.vkGetImageDrmFormatModifierPropertiesEXT(VkDevice::null(), VkImage::null(), &mut properties), VkResult::VK_SUCCESS);
+    }
+    assert_eq!(properties.drmFormatModifier, DRM_FORMAT_MOD_LINEAR);
+}
+
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn drm_format_modifier_struct_sizes() {
+    assert_eq!(std::mem::size_of::<VkDrmFormatModifierPropertiesEXT>(), 16);
+    assert_eq!(std::mem::size_of::<VkDrmFormatModifierProperties2EXT>(), 24);
+    assert_eq!(std::mem::size_of::<VkImageDrmFormatModifierExplicitCreateInfoEXT>(), 40);
+    assert_eq!(std::mem::size_of::<VkPhysicalDeviceImageDrmFormatModifierInfoEXT>(), 40);
+}
+
+#[test]
+fn drm_display() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let acquire_drm_display = VkExtAcquireDrmDisplayCommands::from_entry(&entry, instance()).unwrap();
+    let direct_mode_display = VkExtDirectModeDisplayCommands::from_entry(&entry, instance()).unwrap();
+    let physical_device = VkPhysicalDevice::null();
+    let mut display = VkDisplayKHR::null();
+    unsafe {
+        assert_eq!(acquire_drm_display.vkGetDrmDisplayEXT(physical_device, -1, 7, &mut display), VkResult::VK_ERROR_INITIALIZATION_FAILED);
+        assert_eq!(acquire_drm_display.vkAcquireDrmDisplayEXT(physical_device, 3, display), VkResult::VK_ERROR_INITIALIZATION_FAILED);
+        assert_eq!(acquire_drm_display.vkGetDrmDisplayEXT(physical_device, 3, 7, &mut display), VkResult::VK_SUCCESS);
+        assert_eq!(acquire_drm_display.vkAcquireDrmDisplayEXT(physical_device, 3, display), VkResult::VK_SUCCESS);
+        assert_eq!(direct_mode_display.vkReleaseDisplayEXT(physical_device, display), VkResult::VK_SUCCESS);
+    }
+    assert_eq!(unsafe { transmute::<VkDisplayKHR, u64>(display) }, 0x107);
+}
+
+#[test]
+fn dynamic_rendering() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let dynamic_rendering = VkKhrDynamicRenderingCommands::from_entry(&entry, instance()).unwrap();
+    let color_attachment = VkRenderingAttachmentInfoKHR {
+        sType: VK_STRUCTURE_TYPE_RENDERING_ATTACHMENT_INFO_KHR,
+        pNext: null(),
+        imageView: VkImageView::null(),
+        imageLayout: VkImageLayout::VK_IMAGE_LAYOUT_COLOR_ATTACHMENT_OPTIMAL,
+        resolveMode: VK_RESOLVE_MODE_NONE,
+        resolveImageView: VkImageView::null(),
+        resolveImageLayout: VkImageLayout::VK_IMAGE_LAYOUT_UNDEFINED,
+        loadOp: VkAttachmentLoadOp::VK_ATTACHMENT_LOAD_OP_CLEAR,
+        storeOp: VK_ATTACHMENT_STORE_OP_NONE_KHR,
+        clearValue: VkClearValueUnion::Color(VkClearColorValueUnion::Float32([0.0, 0.0, 0.0, 1.0]).into()).into()
+    };
+    let rendering_info = VkRenderingInfoKHR {
+        sType: VK_STRUCTURE_TYPE_RENDERING_INFO_KHR,
+        pNext: null(),
+        flags: VK_RENDERING_SUSPENDING_BIT_KHR,
+        renderArea: VkRect2D { offset: VkOffset2D { x: 0, y: 0 }, extent: VkExtent2D { width: 640, height: 480 } },
+        layerCount: 1,
+        viewMask: 0,
+        colorAttachmentCount: 1,
+        pColorAttachments: &color_attachment,
+        pDepthAttachment: null(),
+        pStencilAttachment: null()
+    };
+    unsafe {
+        dynamic_rendering.vkCmdBeginRenderingKHR(VkCommandBuffer::null(), &rendering_info);
+        assert_eq!(RENDERING.load(Ordering::SeqCst), 1);
+        dynamic_rendering.vkCmdEndRenderingKHR(VkCommandBuffer::null());
+        assert_eq!(RENDERING.load(Ordering::SeqCst), 0);
+    }
+}
+
+#[test]
+fn synchronization2() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let (synchronization2, report) = VkKhrSynchronization2Commands::from_entry_lenient(&entry, instance());
+    assert_eq!(report.missing, vec!["vkCmdSetEvent2KHR", "vkCmdResetEvent2KHR", "vkCmdWaitEvents2KHR"]);
+    let barrier = VkMemoryBarrier2KHR {
+        sType: VK_STRUCTURE_TYPE_MEMORY_BARRIER_2_KHR,
+        pNext: null(),
+        srcStageMask: VK_PIPELINE_STAGE_2_COPY_BIT_KHR,
+        srcAccessMask: VK_ACCESS_2_TRANSFER_WRITE_BIT_KHR,
+        dstStageMask: VK_PIPELINE_STAGE_2_FRAGMENT_SHADER_BIT_KHR,
+        dstAccessMask: VK_ACCESS_2_SHADER_SAMPLED_READ_BIT_KHR
+    };
+    let dependency_info = VkDependencyInfoKHR {
+        sType: VK_STRUCTURE_TYPE_DEPENDENCY_INFO_KHR,
+        pNext: null(),
+        dependencyFlags: VkDependencyFlags::empty(),
+        memoryBarrierCount: 1,
+        pMemoryBarriers: &barrier,
+        bufferMemoryBarrierCount: 0,
+        pBufferMemoryBarriers: null(),
+        imageMemoryBarrierCount: 0,
+        pImageMemoryBarriers: null()
+    };
+    let wait_info = VkSemaphoreSubmitInfoKHR {
+        sType: VK_STRUCTURE_TYPE_SEMAPHORE_SUBMIT_INFO_KHR,
+        pNext: null(),
+        semaphore: VkSemaphore::null(),
+        value: 0,
+        stageMask: VK_PIPELINE_STAGE_2_VERTEX_ATTRIBUTE_INPUT_BIT_KHR,
+        deviceIndex: 0
+    };
+    let command_buffer_info = VkCommandBufferSubmitInfoKHR {
+        sType: VK_STRUCTURE_TYPE_COMMAND_BUFFER_SUBMIT_INFO_KHR,
+        pNext: null(),
+        commandBuffer: VkCommandBuffer::null(),
+        deviceMask: 0
+    };
+    let submit_info = VkSubmitInfo2KHR {
+        sType: VK_STRUCTURE_TYPE_SUBMIT_INFO_2_KHR,
+        pNext: null(),
+        flags: VkSubmitFlagsKHR::empty(),
+        waitSemaphoreInfoCount: 1,
+        pWaitSemaphoreInfos: &wait_info,
+        commandBufferInfoCount: 1,
+        pCommandBufferInfos: &command_buffer_info,
+        signalSemaphoreInfoCount: 0,
+        pSignalSemaphoreInfos: null()
+    };
+    unsafe {
+        synchronization2.vkCmdPipelineBarrier2KHR(VkCommandBuffer::null(), &dependency_info);
+        synchronization2.vkCmdWriteTimestamp2KHR(VkCommandBuffer::null(), VK_PIPELINE_STAGE_2_BOTTOM_OF_PIPE_BIT_KHR, VkQueryPool::null(), 0);
+        assert_eq!(synchronization2.vkQueueSubmit2KHR(VkQueue::null(), 1, &submit_info, VkFence::null()), VkResult::VK_SUCCESS);
+    }
+    let stages = VkPipelineStageFlags2KHR::from_bits(STAGES.load(Ordering::SeqCst)).unwrap();
+    assert_eq!(stages, VK_PIPELINE_STAGE_2_COPY_BIT | VK_PIPELINE_STAGE_2_FRAGMENT_SHADER_BIT |
+                       VK_PIPELINE_STAGE_2_BOTTOM_OF_PIPE_BIT | VK_PIPELINE_STAGE_2_VERTEX_ATTRIBUTE_INPUT_BIT);
+    assert!(stages.bits() > u32::MAX as u64);
+}
@@ -0,0 +1,187 @@
+#![cfg(unix)]
+#![allow(non_snake_case)]
+
+extern crate dvk;
+extern crate libc;
+
+mod common;
+
+use dvk::core::*;
+use dvk::khr_surface::*;
+use dvk::loader::*;
+use libc::c_char;
+use std::ffi::CStr;
+use std::path::PathBuf;
+use std::ptr::{null, null_mut};
+use std::sync::Arc;
+
+#[test]
+fn default_candidates() {
+    let candidates = LoaderConfig::new().ignore_env().candidates();
+    let expected: Vec<PathBuf> = DEFAULT_LIBRARY_PATHS.iter().map(PathBuf::from).collect();
+    assert_eq!(candidates, expected);
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn linux_library_names() {
+    assert_eq!(DEFAULT_LIBRARY_PATHS, &["libvulkan.so.1", "libvulkan.so"]);
+}
+
+#[test]
+fn explicit_path() {
+    let stub = common::build_stub("loader_explicit", common::DRIVER_STUB);
+    let config = LoaderConfig::new().library_path(&stub);
+    assert_eq!(config.candidates(), vec![stub.clone()]);
+    let core = VkCoreCommands::with_config(&config).unwrap();
+    let mut count = 0;
+    unsafe {
+        assert_eq!(core.vkEnumerateInstanceLayerProperties(&mut count, null_mut()), VkResult::VK_SUCCESS);
+    }
+    assert_eq!(count, 42);
+}
+
+#[test]
+fn missing_library() {
+    let config = LoaderConfig::new().library_path("/nonexistent/libvulkan.so.1");
+    match VkCoreCommands::with_config(&config) {
+        Err(LoadError::LibraryNotFound { tried_paths }) => assert_eq!(tried_paths, vec![PathBuf::from("/nonexistent/libvulkan.so.1")]),
+        Err(error) => panic!("unexpected error: {}", error),
+        Ok(_) => panic!("loaded a nonexistent library"),
+    }
+}
+
+#[test]
+fn missing_entry_point() {
+    let stub = common::build_stub("loader_no_entry", "int vkCreateInstance(void) { return 0; }");
+    match Entry::with_config(&LoaderConfig::new().library_path(&stub)) {
+        Err(LoadError::EntryPointMissing) => {},
+        Err(error) => panic!("unexpected error: {}", error),
+        Ok(_) => panic!("loaded a library without vkGetInstanceProcAddr"),
+    }
+}
+
+#[test]
+fn tables_share_entry() {
+    let stub = common::build_stub("loader_entry", common::DRIVER_STUB);
+    let entry = Entry::with_config(&LoaderConfig::new().library_path(&stub)).unwrap();
+    let mut core = VkCoreCommands::from_entry(&entry).unwrap();
+    let mut instance = VkInstance::null();
+    unsafe {
+        assert_eq!(core.vkCreateInstance(null(), null(), &mut instance), VkResult::VK_SUCCESS);
+    }
+    core.load(instance).unwrap();
+    let surface = VkKhrSurfaceCommands::from_entry(&entry, instance).unwrap();
+    assert_eq!(Arc::strong_count(entry.library().unwrap()), 3);
+    drop(core);
+    drop(surface);
+    assert_eq!(Arc::strong_count(entry.library().unwrap()), 1);
+}
+
+unsafe extern "system" fn fake_create_instance(_pCreateInfo: *const VkInstanceCreateInfo,
+                                               _pAllocator: *const VkAllocationCallbacks,
+                                               _pInstance: *mut VkInstance) -> VkResult {
+    VkResult::VK_ERROR_INCOMPATIBLE_DRIVER
+}
+
+unsafe extern "system" fn fake_enumerate_instance_extension_properties(_pLayerName: *const c_char,
+                                                                        pPropertyCount: *mut u32,
+                                                                        _pProperties: *mut VkExtensionProperties) -> VkResult {
+    *pPropertyCount = 0;
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_enumerate_instance_layer_properties(pPropertyCount: *mut u32,
+                                                                    _pProperties: *mut VkLayerProperties) -> VkResult {
+    *pPropertyCount = 7;
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_get_instance_proc_addr(_instance: VkInstance, pName: *const c_char) -> vkVoidFunctionFn {
+    match CStr::from_ptr(pName).to_str().unwrap() {
+        "vkCreateInstance" => fake_create_instance as vkCreateInstanceFn as vkVoidFunctionFn,
+        "vkEnumerateInstanceExtensionProperties" => fake_enumerate_instance_extension_properties as vkEnumerateInstanceExtensionPropertiesFn as vkVoidFunctionFn,
+        "vkEnumerateInstanceLayerProperties" => fake_enumerate_instance_layer_properties as vkEnumerateInstanceLayerPropertiesFn as vkVoidFunctionFn,
+        _ => null()
+    }
+}
+
+#[test]
+fn in_process_proc_addr() {
+    let core = unsafe { VkCoreCommands::from_proc_addr(fake_get_instance_proc_addr).unwrap() };
+    let mut count = 0;
+    unsafe {
+        assert_eq!(core.vkEnumerateInstanceLayerProperties(&mut count, null_mut()), VkResult::VK_SUCCESS);
+    }
+    assert_eq!(count, 7);
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    assert!(entry.library().is_none());
+    match VkKhrSurfaceCommands::from_entry(&entry, VkInstance::null()) {
+        Err(LoadError::CommandMissing { name, instance }) => {
+            assert_eq!(name, "vkDestroySurfaceKHR");
+            assert!(instance.is_null());
+        },
+        Err(error) => panic!("unexpected error: {}", error),
+        Ok(_) => panic!("loaded surface commands from a fake without them"),
+    }
+}
+
+#[test]
+fn unloaded_commands() {
+    let stub = common::build_stub("loader_unloaded", common::DRIVER_STUB);
+    let core = VkCoreCommands::with_config(&LoaderConfig::new().library_path(&stub)).unwrap();
+    assert!(core.is_loaded("vkCreateInstance"));
+    assert!(!core.is_loaded("vkCmdDraw"));
+    assert!(!core.is_loaded("vkNotACommand"));
+    assert_eq!(core.loaded_commands(), vec!["vkCreateInstance",
+                                            "vkGetInstanceProcAddr",
+                                            "vkEnumerateInstanceExtensionProperties",
+                                            "vkEnumerateInstanceLayerProperties"]);
+    let result = unsafe { core.try_vkCmdDraw(VkCommandBuffer::null(), 3, 1, 0, 0) };
+    assert_eq!(result, Err(NotLoaded { command: "vkCmdDraw" }));
+}
+
+unsafe extern "system" fn fake_partial_proc_addr(instance: VkInstance, pName: *const c_char) -> vkVoidFunctionFn {
+    match CStr::from_ptr(pName).to_str().unwrap() {
+        "vkDestroySurfaceKHR" | "vkGetPhysicalDeviceSurfacePresentModesKHR" => null(),
+        _ if !instance.is_null() => common::noop(),
+        _ => fake_get_instance_proc_addr(instance, pName)
+    }
+}
+
+#[test]
+fn lenient_load() {
+    let entry = unsafe { Entry::from_proc_addr(fake_partial_proc_addr) };
+    let instance = common::instance();
+    let (surface, report) = VkKhrSurfaceCommands::from_entry_lenient(&entry, instance);
+    assert!(!report.is_complete());
+    assert_eq!(report.missing, vec!["vkDestroySurfaceKHR", "vkGetPhysicalDeviceSurfacePresentModesKHR"]);
+    assert_eq!(surface.loaded_commands(), vec!["vkGetPhysicalDeviceSurfaceSupportKHR",
+                                               "vkGetPhysicalDeviceSurfaceCapabilitiesKHR",
+                                               "vkGetPhysicalDeviceSurfaceFormatsKHR"]);
+    match VkKhrSurfaceCommands::from_entry(&entry, instance) {
+        Err(LoadError::CommandMissing { name, .. }) => assert_eq!(name, "vkDestroySurfaceKHR"),
+        Err(error) => panic!("unexpected error: {}", error),
+        Ok(_) => panic!("strict load succeeded with missing commands"),
+    }
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn lazy_load() {
+    assert_send_sync::<VkCoreCommands>();
+    assert_send_sync::<VkKhrSurfaceCommands>();
+    let entry = unsafe { Entry::from_proc_addr(fake_partial_proc_addr) };
+    let instance = common::instance();
+    let mut core = VkCoreCommands::from_entry(&entry).unwrap();
+    core.load_lazy(instance);
+    assert!(!core.is_loaded("vkDestroyInstance"));
+    assert_eq!(unsafe { core.try_vkDestroyInstance(instance, null()) }, Ok(()));
+    assert!(core.is_loaded("vkDestroyInstance"));
+    assert!(!core.is_loaded("vkCreateDevice"));
+    let surface = VkKhrSurfaceCommands::from_entry_lazy(&entry, instance);
+    assert!(surface.loaded_commands().is_empty());
+    let result = unsafe { surface.try_vkDestroySurfaceKHR(instance, VkSurfaceKHR::null(), null()) };
+    assert_eq!(result, Err(NotLoaded { command: "vkDestroySurfaceKHR" }));
+}
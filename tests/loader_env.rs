@@ -0,0 +1,24 @@
+#![cfg(unix)]
+
+extern crate dvk;
+
+mod common;
+
+use dvk::core::*;
+use dvk::loader::*;
+use std::env;
+
+// Changing the environment is process-wide, so this test has a binary of its own where no other
+// test reads DVK_VULKAN_LIBRARY concurrently
+#[test]
+fn env_override() {
+    let stub = common::build_stub("loader_env", common::DRIVER_STUB);
+    env::set_var(VULKAN_LIBRARY_ENV, &stub);
+    let candidates = LoaderConfig::new().candidates();
+    let ignored = LoaderConfig::new().ignore_env().candidates();
+    let core = VkCoreCommands::new();
+    env::remove_var(VULKAN_LIBRARY_ENV);
+    assert_eq!(candidates, vec![stub]);
+    assert_ne!(ignored, candidates);
+    assert!(core.is_ok());
+}
@@ -0,0 +1,117 @@
+#![allow(non_snake_case)]
+
+extern crate dvk;
+extern crate libc;
+
+mod common;
+
+use common::instance;
+use dvk::core::*;
+use dvk::khr_surface::*;
+use dvk::khr_xlib_surface::*;
+use dvk::khr_xcb_surface::*;
+use dvk::khr_wayland_surface::*;
+use dvk::ext_headless_surface::*;
+use dvk::loader::*;
+use libc::{c_char, c_void};
+use std::mem::transmute;
+use std::ptr::null;
+use std::sync::Mutex;
+
+// Instance, structure type, pNext and whether the flags were empty in the last vkCreateHeadlessSurfaceEXT call
+static HEADLESS_CREATE_INFO: Mutex<Option<(usize, VkStructureType, usize, bool)>> = Mutex::new(None);
+
+unsafe extern "system" fn fake_get_xlib_presentation_support(_physicalDevice: VkPhysicalDevice,
+                                                              queueFamilyIndex: u32,
+                                                              dpy: *mut dvk::khr_xlib_surface::platform::Display,
+                                                              visualID: dvk::khr_xlib_surface::platform::VisualID) -> VkBool32 {
+    (queueFamilyIndex == 2 && dpy as usize == 0x10 && visualID == 0x21) as VkBool32
+}
+
+unsafe extern "system" fn fake_get_xcb_presentation_support(_physicalDevice: VkPhysicalDevice,
+                                                             queueFamilyIndex: u32,
+                                                             connection: *mut dvk::khr_xcb_surface::platform::xcb_connection_t,
+                                                             visual_id: dvk::khr_xcb_surface::platform::xcb_visualid_t) -> VkBool32 {
+    (queueFamilyIndex == 2 && connection as usize == 0x10 && visual_id == 0x21) as VkBool32
+}
+
+unsafe extern "system" fn fake_get_wayland_presentation_support(_physicalDevice: VkPhysicalDevice,
+                                                                 queueFamilyIndex: u32,
+                                                                 display: *mut dvk::khr_wayland_surface::platform::wl_display) -> VkBool32 {
+    (queueFamilyIndex == 2 && display as usize == 0x10) as VkBool32
+}
+
+unsafe extern "system" fn fake_create_headless_surface(instance: VkInstance,
+                                                       pCreateInfo: *const VkHeadlessSurfaceCreateInfoEXT,
+                                                       _pAllocator: *const VkAllocationCallbacks,
+                                                       pSurface: *mut VkSurfaceKHR) -> VkResult {
+    let create_info = &*pCreateInfo;
+    *HEADLESS_CREATE_INFO.lock().unwrap() = Some((transmute::<VkInstance, usize>(instance), create_info.sType, create_info.pNext as usize, create_info.flags == VkHeadlessSurfaceCreateFlagsEXT::empty()));
+    *pSurface = transmute::<u64, VkSurfaceKHR>(0x30);
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_get_instance_proc_addr(instance: VkInstance, pName: *const c_char) -> vkVoidFunctionFn {
+    common::lookup(&[("vkGetPhysicalDeviceXlibPresentationSupportKHR", fake_get_xlib_presentation_support as vkGetPhysicalDeviceXlibPresentationSupportKHRFn as vkVoidFunctionFn),
+                     ("vkGetPhysicalDeviceXcbPresentationSupportKHR", fake_get_xcb_presentation_support as vkGetPhysicalDeviceXcbPresentationSupportKHRFn as vkVoidFunctionFn),
+                     ("vkGetPhysicalDeviceWaylandPresentationSupportKHR", fake_get_wayland_presentation_support as vkGetPhysicalDeviceWaylandPresentationSupportKHRFn as vkVoidFunctionFn),
+                     ("vkCreateHeadlessSurfaceEXT", fake_create_headless_surface as vkCreateHeadlessSurfaceEXTFn as vkVoidFunctionFn)],
+                   instance, pName)
+}
+
+#[test]
+fn xlib_surface() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    match VkKhrXlibSurfaceCommands::from_entry(&entry, instance()) {
+        Err(LoadError::CommandMissing { name, .. }) => assert_eq!(name, "vkCreateXlibSurfaceKHR"),
+        _ => panic!("loaded a table with a missing command")
+    }
+    let (xlib_surface, report) = VkKhrXlibSurfaceCommands::from_entry_lenient(&entry, instance());
+    assert_eq!(report.missing, vec!["vkCreateXlibSurfaceKHR"]);
+    unsafe {
+        let display = 0x10usize as *mut dvk::khr_xlib_surface::platform::Display;
+        assert_eq!(xlib_surface.vkGetPhysicalDeviceXlibPresentationSupportKHR(VkPhysicalDevice::null(), 2, display, 0x21), VK_TRUE);
+        assert_eq!(xlib_surface.vkGetPhysicalDeviceXlibPresentationSupportKHR(VkPhysicalDevice::null(), 1, display, 0x21), VK_FALSE);
+        assert!(xlib_surface.try_vkCreateXlibSurfaceKHR(instance(), null(), null(), &mut VkSurfaceKHR::null()).is_err());
+    }
+}
+
+#[test]
+fn xcb_surface() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let (xcb_surface, report) = VkKhrXcbSurfaceCommands::from_entry_lenient(&entry, instance());
+    assert_eq!(report.missing, vec!["vkCreateXcbSurfaceKHR"]);
+    unsafe {
+        let connection = 0x10usize as *mut dvk::khr_xcb_surface::platform::xcb_connection_t;
+        assert_eq!(xcb_surface.vkGetPhysicalDeviceXcbPresentationSupportKHR(VkPhysicalDevice::null(), 2, connection, 0x21), VK_TRUE);
+        assert_eq!(xcb_surface.vkGetPhysicalDeviceXcbPresentationSupportKHR(VkPhysicalDevice::null(), 2, connection, 0x22), VK_FALSE);
+    }
+}
+
+#[test]
+fn wayland_surface() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let wayland_surface = VkKhrWaylandSurfaceCommands::from_entry_lazy(&entry, instance());
+    assert!(!wayland_surface.is_loaded("vkCreateWaylandSurfaceKHR"));
+    unsafe {
+        let display = 0x10usize as *mut dvk::khr_wayland_surface::platform::wl_display;
+        assert_eq!(wayland_surface.vkGetPhysicalDeviceWaylandPresentationSupportKHR(VkPhysicalDevice::null(), 2, display), VK_TRUE);
+        assert!(wayland_surface.is_loaded("vkGetPhysicalDeviceWaylandPresentationSupportKHR"));
+    }
+}
+
+#[test]
+fn headless_surface() {
+    let headless_surface = unsafe { VkExtHeadlessSurfaceCommands::from_proc_addr(fake_get_instance_proc_addr, instance()).unwrap() };
+    let create_info = VkHeadlessSurfaceCreateInfoEXT {
+        sType: VkStructureType::VK_STRUCTURE_TYPE_HEADLESS_SURFACE_CREATE_INFO_EXT,
+        pNext: 0x50usize as *const c_void,
+        flags: VkHeadlessSurfaceCreateFlagsEXT::empty()
+    };
+    let mut surface = VkSurfaceKHR::null();
+    unsafe {
+        assert_eq!(headless_surface.vkCreateHeadlessSurfaceEXT(instance(), &create_info, null(), &mut surface), VkResult::VK_SUCCESS);
+        assert_eq!(transmute::<VkSurfaceKHR, u64>(surface), 0x30);
+    }
+    assert_eq!(*HEADLESS_CREATE_INFO.lock().unwrap(), Some((1, VkStructureType::VK_STRUCTURE_TYPE_HEADLESS_SURFACE_CREATE_INFO_EXT, 0x50, true)));
+}
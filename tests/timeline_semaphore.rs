@@ -0,0 +1,92 @@
+#![allow(non_snake_case)]
+
+extern crate dvk;
+extern crate libc;
+
+mod common;
+
+use dvk::core::*;
+use dvk::khr_timeline_semaphore::*;
+use dvk::loader::NotLoaded;
+use libc::c_char;
+use std::mem::transmute;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+static LAST_TIMEOUT: AtomicU64 = AtomicU64::new(0);
+
+unsafe extern "system" fn fake_get_counter_value(_device: VkDevice, _semaphore: VkSemaphore, pValue: *mut u64) -> VkResult {
+    *pValue = COUNTER.load(Ordering::SeqCst);
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_wait_semaphores(_device: VkDevice, pWaitInfo: *const VkSemaphoreWaitInfoKHR, timeout: u64) -> VkResult {
+    LAST_TIMEOUT.store(timeout, Ordering::SeqCst);
+    if (*pWaitInfo).semaphoreCount != 1 {
+        return VkResult::VK_ERROR_INITIALIZATION_FAILED;
+    }
+    let value = *(*pWaitInfo).pValues;
+    for _ in 0..100 {
+        if COUNTER.load(Ordering::SeqCst) >= value {
+            return VkResult::VK_SUCCESS;
+        }
+        if timeout == 0 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    VkResult::VK_TIMEOUT
+}
+
+unsafe extern "system" fn fake_signal_semaphore(_device: VkDevice, pSignalInfo: *const VkSemaphoreSignalInfoKHR) -> VkResult {
+    let value = (*pSignalInfo).value;
+    if value <= COUNTER.load(Ordering::SeqCst) {
+        return VkResult::VK_ERROR_DEVICE_LOST;
+    }
+    COUNTER.store(value, Ordering::SeqCst);
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_get_instance_proc_addr(instance: VkInstance, pName: *const c_char) -> vkVoidFunctionFn {
+    common::lookup(&[("vkGetSemaphoreCounterValueKHR", fake_get_counter_value as vkGetSemaphoreCounterValueKHRFn as vkVoidFunctionFn),
+                     ("vkWaitSemaphoresKHR", fake_wait_semaphores as vkWaitSemaphoresKHRFn as vkVoidFunctionFn),
+                     ("vkSignalSemaphoreKHR", fake_signal_semaphore as vkSignalSemaphoreKHRFn as vkVoidFunctionFn)],
+                   instance, pName)
+}
+
+#[test]
+fn wait_and_signal() {
+    let instance = common::instance();
+    let commands = unsafe { VkKhrTimelineSemaphoreCommands::from_proc_addr(fake_get_instance_proc_addr, instance).unwrap() };
+    let semaphore = unsafe { TimelineSemaphore::new(&commands, VkDevice::null(), transmute::<u64, VkSemaphore>(0x50)) };
+    assert_eq!(unsafe { transmute::<VkSemaphore, u64>(semaphore.handle()) }, 0x50);
+    assert_eq!(semaphore.value(), Ok(0));
+    assert_eq!(semaphore.wait(1, Duration::from_secs(0)), Ok(false));
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(20));
+            unsafe { semaphore.signal(2) }.unwrap();
+        });
+        assert_eq!(semaphore.wait(2, Duration::from_secs(5)), Ok(true));
+    });
+    assert_eq!(LAST_TIMEOUT.load(Ordering::SeqCst), 5_000_000_000);
+    assert_eq!(semaphore.value(), Ok(2));
+    assert_eq!(unsafe { semaphore.signal(1) }, Err(TimelineSemaphoreError::Vulkan(VkResult::VK_ERROR_DEVICE_LOST)));
+
+    assert_eq!(semaphore.wait(2, Duration::from_secs(u64::MAX)), Ok(true));
+    assert_eq!(LAST_TIMEOUT.load(Ordering::SeqCst), u64::MAX);
+}
+
+#[test]
+fn unloaded_commands() {
+    let mut commands = unsafe { VkKhrTimelineSemaphoreCommands::from_proc_addr(fake_get_instance_proc_addr, common::instance()).unwrap() };
+    // The fake returns no commands for a null instance
+    assert!(!commands.load_lenient(VkInstance::null()).is_complete());
+    let semaphore = unsafe { TimelineSemaphore::new(&commands, VkDevice::null(), VkSemaphore::null()) };
+    assert_eq!(semaphore.value(), Err(TimelineSemaphoreError::NotLoaded(NotLoaded { command: "vkGetSemaphoreCounterValueKHR" })));
+    assert!(semaphore.wait(1, Duration::from_secs(0)).is_err());
+    assert!(unsafe { semaphore.signal(1) }.is_err());
+}
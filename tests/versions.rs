@@ -0,0 +1,127 @@
+#![allow(non_snake_case)]
+
+#[macro_use]
+extern crate dvk;
+extern crate libc;
+
+mod common;
+
+use dvk::core::*;
+use dvk::core_1_1::*;
+use dvk::core_1_2::*;
+use dvk::core_1_3::*;
+use dvk::loader::*;
+use libc::c_char;
+use std::ffi::CStr;
+use std::mem::size_of;
+use std::ptr::null;
+
+unsafe extern "system" fn fake_enumerate_instance_version(pApiVersion: *mut u32) -> VkResult {
+    *pApiVersion = VK_MAKE_VERSION!(1,1,101);
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_get_instance_proc_addr(instance: VkInstance, pName: *const c_char) -> vkVoidFunctionFn {
+    match CStr::from_ptr(pName).to_str().unwrap() {
+        "vkEnumerateInstanceVersion" => fake_enumerate_instance_version as vkEnumerateInstanceVersionFn as vkVoidFunctionFn,
+        _ if !instance.is_null() => common::noop(),
+        _ => null()
+    }
+}
+
+unsafe extern "system" fn fake_1_0_proc_addr(instance: VkInstance, pName: *const c_char) -> vkVoidFunctionFn {
+    match CStr::from_ptr(pName).to_str().unwrap() {
+        "vkEnumerateInstanceVersion" => null(),
+        _ => fake_get_instance_proc_addr(instance, pName)
+    }
+}
+
+#[test]
+fn instance_version() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let core_1_1 = VkCore11Commands::from_entry_lazy(&entry, VkInstance::null(), 0);
+    assert!(core_1_1.is_loaded("vkEnumerateInstanceVersion"));
+    assert_eq!(core_1_1.instance_version(), VK_MAKE_VERSION!(1,1,101));
+    let old_entry = unsafe { Entry::from_proc_addr(fake_1_0_proc_addr) };
+    let old_core_1_1 = VkCore11Commands::from_entry_lazy(&old_entry, VkInstance::null(), 0);
+    assert!(!old_core_1_1.is_loaded("vkEnumerateInstanceVersion"));
+    assert_eq!(old_core_1_1.instance_version(), VK_API_VERSION_1_0);
+}
+
+#[test]
+fn version_checked_load() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let instance = common::instance();
+    match VkCore11Commands::from_entry(&entry, instance, VK_API_VERSION_1_0) {
+        Err(LoadError::VersionNotSupported { required, version }) => {
+            assert_eq!(required, VK_API_VERSION_1_1);
+            assert_eq!(version, VK_API_VERSION_1_0);
+        },
+        Err(error) => panic!("unexpected error: {}", error),
+        Ok(_) => panic!("loaded Vulkan 1.1 commands for a 1.0 instance"),
+    }
+    let (core_1_1, report) = VkCore11Commands::from_entry_lenient(&entry, instance, VK_API_VERSION_1_0);
+    assert!(report.is_complete());
+    assert!(!core_1_1.is_loaded("vkGetPhysicalDeviceFeatures2"));
+    let lazy = VkCore11Commands::from_entry_lazy(&entry, instance, VK_API_VERSION_1_0);
+    let result = unsafe { lazy.try_vkCmdSetDeviceMask(VkCommandBuffer::null(), 1) };
+    assert_eq!(result, Err(NotLoaded { command: "vkCmdSetDeviceMask" }));
+    let core_1_1 = VkCore11Commands::from_entry(&entry, instance, VK_API_VERSION_1_1).unwrap();
+    assert!(core_1_1.is_loaded("vkGetPhysicalDeviceFeatures2"));
+    assert!(core_1_1.is_loaded("vkGetDeviceQueue2"));
+}
+
+#[test]
+fn version_error_message() {
+    let error = LoadError::VersionNotSupported { required: VK_API_VERSION_1_1, version: VK_API_VERSION_1_0 };
+    assert_eq!(error.to_string(), "Vulkan 1.1 required, instance version is 1.0");
+}
+
+#[test]
+fn newer_versions() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let instance = common::instance();
+    let core_1_2 = VkCore12Commands::from_entry(&entry, instance, VK_API_VERSION_1_2).unwrap();
+    assert!(core_1_2.is_loaded("vkWaitSemaphores"));
+    match VkCore13Commands::from_entry(&entry, instance, VK_API_VERSION_1_2) {
+        Err(LoadError::VersionNotSupported { required, .. }) => assert_eq!(required, VK_API_VERSION_1_3),
+        Err(error) => panic!("unexpected error: {}", error),
+        Ok(_) => panic!("loaded Vulkan 1.3 commands for a 1.2 instance"),
+    }
+    let (core_1_3, report) = VkCore13Commands::from_entry_lenient(&entry, instance, VK_API_VERSION_1_2);
+    assert!(report.is_complete());
+    assert!(core_1_3.loaded_commands().is_empty());
+    let core_1_3 = VkCore13Commands::from_entry(&entry, instance, VK_MAKE_VERSION!(1,3,250)).unwrap();
+    assert!(core_1_3.is_loaded("vkCmdSetCullMode"));
+    assert!(core_1_3.is_loaded("vkCmdBeginRendering"));
+}
+
+#[test]
+fn reload_older_instance() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let instance = common::instance();
+    let mut core_1_1 = VkCore11Commands::from_entry(&entry, instance, VK_API_VERSION_1_1).unwrap();
+    assert!(core_1_1.load_lenient(instance, VK_API_VERSION_1_0).is_complete());
+    assert_eq!(core_1_1.loaded_commands(), vec!["vkEnumerateInstanceVersion"]);
+    let result = unsafe { core_1_1.try_vkTrimCommandPool(VkDevice::null(), VkCommandPool::null(), VkCommandPoolTrimFlags::empty()) };
+    assert_eq!(result, Err(NotLoaded { command: "vkTrimCommandPool" }));
+
+    let mut core_1_2 = VkCore12Commands::from_entry(&entry, instance, VK_API_VERSION_1_2).unwrap();
+    core_1_2.load_lazy(instance, VK_API_VERSION_1_1);
+    assert!(!core_1_2.is_loaded("vkWaitSemaphores"));
+
+    let mut core_1_3 = VkCore13Commands::from_entry(&entry, instance, VK_API_VERSION_1_3).unwrap();
+    assert!(core_1_3.load_lenient(instance, VK_API_VERSION_1_2).is_complete());
+    assert!(!core_1_3.is_loaded("vkCmdBeginRendering"));
+}
+
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn feature_struct_sizes() {
+    assert_eq!(size_of::<VkPhysicalDeviceVulkan11Features>(), 64);
+    assert_eq!(size_of::<VkPhysicalDeviceVulkan12Features>(), 208);
+    assert_eq!(size_of::<VkPhysicalDeviceVulkan12Properties>(), 736);
+    assert_eq!(size_of::<VkPhysicalDeviceVulkan13Features>(), 80);
+    assert_eq!(size_of::<VkPhysicalDeviceVulkan13Properties>(), 216);
+    assert_eq!(size_of::<VkPipelineStageFlags2>(), 8);
+}
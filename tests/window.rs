@@ -0,0 +1,94 @@
+#![cfg(feature = "raw-window-handle")]
+#![allow(non_snake_case)]
+
+extern crate dvk;
+extern crate libc;
+extern crate raw_window_handle;
+
+mod common;
+
+use dvk::core::*;
+use dvk::khr_surface::*;
+use dvk::khr_xlib_surface::*;
+use dvk::khr_xcb_surface::*;
+use dvk::loader::*;
+use dvk::window::*;
+use libc::c_char;
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle, XlibDisplayHandle, XlibWindowHandle,
+                        XcbDisplayHandle, XcbWindowHandle, WaylandDisplayHandle, AppKitDisplayHandle};
+use std::ffi::CStr;
+use std::mem::transmute;
+use std::num::NonZeroU32;
+use std::ptr::{null, NonNull};
+
+unsafe extern "system" fn fake_create_xlib_surface(_instance: VkInstance,
+                                                   pCreateInfo: *const VkXlibSurfaceCreateInfoKHR,
+                                                   _pAllocator: *const VkAllocationCallbacks,
+                                                   pSurface: *mut VkSurfaceKHR) -> VkResult {
+    if (*pCreateInfo).dpy as usize != 0x10 || (*pCreateInfo).window != 0x20 {
+        return VkResult::VK_ERROR_INITIALIZATION_FAILED;
+    }
+    *pSurface = transmute::<u64, VkSurfaceKHR>(0x30);
+    VkResult::VK_SUCCESS
+}
+
+unsafe extern "system" fn fake_create_xcb_surface(_instance: VkInstance,
+                                                  _pCreateInfo: *const VkXcbSurfaceCreateInfoKHR,
+                                                  _pAllocator: *const VkAllocationCallbacks,
+                                                  _pSurface: *mut VkSurfaceKHR) -> VkResult {
+    VkResult::VK_ERROR_OUT_OF_HOST_MEMORY
+}
+
+unsafe extern "system" fn fake_get_instance_proc_addr(instance: VkInstance, pName: *const c_char) -> vkVoidFunctionFn {
+    common::lookup(&[("vkCreateXlibSurfaceKHR", fake_create_xlib_surface as vkCreateXlibSurfaceKHRFn as vkVoidFunctionFn),
+                     ("vkCreateXcbSurfaceKHR", fake_create_xcb_surface as vkCreateXcbSurfaceKHRFn as vkVoidFunctionFn),
+                     ("vkGetPhysicalDeviceXlibPresentationSupportKHR", common::noop()),
+                     ("vkGetPhysicalDeviceXcbPresentationSupportKHR", common::noop())],
+                   instance, pName)
+}
+
+fn xlib_handles() -> (RawDisplayHandle, RawWindowHandle) {
+    let display = XlibDisplayHandle::new(NonNull::new(0x10usize as *mut _), 0);
+    (RawDisplayHandle::Xlib(display), RawWindowHandle::Xlib(XlibWindowHandle::new(0x20)))
+}
+
+#[test]
+fn required_extensions() {
+    let (display, _) = xlib_handles();
+    let names: Vec<&str> = dvk::window::required_extensions(display).unwrap().iter()
+        .map(|&name| unsafe { CStr::from_ptr(name) }.to_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["VK_KHR_surface", "VK_KHR_xlib_surface"]);
+    let wayland = RawDisplayHandle::Wayland(WaylandDisplayHandle::new(NonNull::dangling()));
+    assert_eq!(dvk::window::required_extensions(wayland).unwrap().len(), 2);
+    match dvk::window::required_extensions(RawDisplayHandle::AppKit(AppKitDisplayHandle::new())) {
+        Err(SurfaceError::UnsupportedWindow) => {},
+        _ => panic!("listed extensions for a window system without a surface module")
+    }
+}
+
+#[test]
+fn create_surface() {
+    let entry = unsafe { Entry::from_proc_addr(fake_get_instance_proc_addr) };
+    let instance = common::instance();
+    let (display, window) = xlib_handles();
+    let surface = unsafe { dvk::window::create_surface(&entry, instance, display, window, null()).unwrap() };
+    assert_eq!(unsafe { transmute::<VkSurfaceKHR, u64>(surface) }, 0x30);
+
+    let xcb_display = RawDisplayHandle::Xcb(XcbDisplayHandle::new(NonNull::new(0x10usize as *mut _), 0));
+    let xcb_window = RawWindowHandle::Xcb(XcbWindowHandle::new(NonZeroU32::new(0x20).unwrap()));
+    match unsafe { dvk::window::create_surface(&entry, instance, xcb_display, xcb_window, null()) } {
+        Err(SurfaceError::Vulkan(VkResult::VK_ERROR_OUT_OF_HOST_MEMORY)) => {},
+        _ => panic!("surface creation error was not reported")
+    }
+    match unsafe { dvk::window::create_surface(&entry, instance, xcb_display, window, null()) } {
+        Err(SurfaceError::UnsupportedWindow) => {},
+        _ => panic!("created a surface for mismatched handles")
+    }
+    let wayland = RawDisplayHandle::Wayland(WaylandDisplayHandle::new(NonNull::dangling()));
+    let wayland_window = RawWindowHandle::Wayland(raw_window_handle::WaylandWindowHandle::new(NonNull::dangling()));
+    match unsafe { dvk::window::create_surface(&entry, instance, wayland, wayland_window, null()) } {
+        Err(SurfaceError::Load(LoadError::CommandMissing { name, .. })) => assert_eq!(name, "vkCreateWaylandSurfaceKHR"),
+        _ => panic!("created a surface without the extension commands")
+    }
+}